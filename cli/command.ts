@@ -3,8 +3,28 @@ import { PublicKey } from "@solana/web3.js";
 import {
   configProject,
   createBondingCurve,
+  migrateConfig,
+  migrateBondingCurve,
+  migrateSolVault,
+  proposeAuthority,
+  acceptAuthority,
+  setFees,
+  settleConfigChange,
+  setPaused,
+  freezeCurve,
+  setWhitelistEnabled,
+  updateWhitelist,
+  setPresaleAllowance,
+  claimVested,
+  claimCreatorFees,
+  proposeCreator,
+  acceptCreator,
+  cancelCreatorTransfer,
+  registerReferrer,
+  cancelAuthorityTransfer,
   setClusterConfig,
   swap,
+  swapExactOut,
   initMigrationTx,
   getCurrentPrice,
   calculateSwap,
@@ -47,12 +67,289 @@ programCommand("curve").action(async (directory, cmd) => {
   await createBondingCurve();
 });
 
+programCommand("migrate-config").action(async (directory, cmd) => {
+  const { env, keypair, rpc } = cmd.opts();
+
+  await setClusterConfig(env, keypair, rpc);
+
+  await migrateConfig();
+});
+
+programCommand("migrate-curve")
+  .option("-t, --token <string>", "token address")
+  .action(async (directory, cmd) => {
+    const { env, keypair, rpc, token } = cmd.opts();
+
+    await setClusterConfig(env, keypair, rpc);
+
+    if (token === undefined) {
+      console.log("Error token address");
+      return;
+    }
+
+    await migrateBondingCurve(new PublicKey(token));
+  });
+
+//  run `migrate-curve` for the token first
+programCommand("migrate-vault")
+  .option("-t, --token <string>", "token address")
+  .action(async (directory, cmd) => {
+    const { env, keypair, rpc, token } = cmd.opts();
+
+    await setClusterConfig(env, keypair, rpc);
+
+    if (token === undefined) {
+      console.log("Error token address");
+      return;
+    }
+
+    await migrateSolVault(new PublicKey(token));
+  });
+
+programCommand("config-change")
+  .option("-c, --cancel", "cancel the queued change instead of executing it")
+  .action(async (directory, cmd) => {
+    const { env, keypair, rpc, cancel } = cmd.opts();
+
+    await setClusterConfig(env, keypair, rpc);
+
+    await settleConfigChange(!!cancel);
+  });
+
+programCommand("set-fees")
+  .option("-b, --buy <number>", "platform buy fee in basis points")
+  .option("-s, --sell <number>", "platform sell fee in basis points")
+  .option("-m, --migration <number>", "platform migration fee in basis points")
+  .option("-c, --creator <number>", "creator fee on buys and sells in basis points", "0")
+  .option("-r, --referral <number>", "referrers' slice of the platform fee in basis points", "0")
+  .action(async (directory, cmd) => {
+    const { env, keypair, rpc, buy, sell, migration, creator, referral } = cmd.opts();
+
+    await setClusterConfig(env, keypair, rpc);
+
+    if (buy === undefined || sell === undefined || migration === undefined) {
+      console.log("Error fee amounts");
+      return;
+    }
+
+    await setFees(
+      Number(buy),
+      Number(sell),
+      Number(migration),
+      Number(creator),
+      Number(referral)
+    );
+  });
+
+programCommand("pause")
+  .option("-f, --flags <number>", "1: create, 2: buy, 4: sell, 8: migrate, summed")
+  .option("-u, --unpause", "resume the flagged actions instead")
+  .action(async (directory, cmd) => {
+    const { env, keypair, rpc, flags, unpause } = cmd.opts();
+
+    await setClusterConfig(env, keypair, rpc);
+
+    if (flags === undefined) {
+      console.log("Error pause flags");
+      return;
+    }
+
+    await setPaused(Number(flags), !unpause);
+  });
+
+programCommand("freeze")
+  .option("-t, --token <string>", "token address")
+  .option("-u, --unfreeze", "unfreeze the curve instead")
+  .action(async (directory, cmd) => {
+    const { env, keypair, rpc, token, unfreeze } = cmd.opts();
+
+    await setClusterConfig(env, keypair, rpc);
+
+    if (token === undefined) {
+      console.log("Error token address");
+      return;
+    }
+
+    await freezeCurve(new PublicKey(token), !unfreeze);
+  });
+
+programCommand("whitelist")
+  .option("-c, --creators <string>", "comma separated creator addresses")
+  .option("-r, --remove", "remove a single creator instead")
+  .option("-e, --enable", "require whitelisted creators")
+  .option("-d, --disable", "let anyone create curves again")
+  .action(async (directory, cmd) => {
+    const { env, keypair, rpc, creators, remove, enable, disable } = cmd.opts();
+
+    await setClusterConfig(env, keypair, rpc);
+
+    if (enable || disable) {
+      await setWhitelistEnabled(!!enable);
+      return;
+    }
+
+    if (creators === undefined) {
+      console.log("Error creator addresses");
+      return;
+    }
+
+    const keys = creators.split(",").map((key: string) => new PublicKey(key.trim()));
+    if (remove && keys.length !== 1) {
+      console.log("Error remove takes a single creator");
+      return;
+    }
+
+    await updateWhitelist(keys, !!remove);
+  });
+
+programCommand("presale")
+  .option("-t, --token <string>", "token address")
+  .option("-w, --wallet <string>", "wallet to allowlist")
+  .option("-c, --cap <number>", "lamports the wallet may spend in the presale")
+  .action(async (directory, cmd) => {
+    const { env, keypair, rpc, token, wallet, cap } = cmd.opts();
+
+    await setClusterConfig(env, keypair, rpc);
+
+    if (token === undefined || wallet === undefined || cap === undefined) {
+      console.log("Error token, wallet or cap");
+      return;
+    }
+
+    await setPresaleAllowance(new PublicKey(token), new PublicKey(wallet), Number(cap));
+  });
+
+programCommand("claim-vested")
+  .option("-t, --token <string>", "token address")
+  .action(async (directory, cmd) => {
+    const { env, keypair, rpc, token } = cmd.opts();
+
+    await setClusterConfig(env, keypair, rpc);
+
+    if (token === undefined) {
+      console.log("Error token address");
+      return;
+    }
+
+    await claimVested(new PublicKey(token));
+  });
+
+programCommand("register-referrer").action(async (directory, cmd) => {
+  const { env, keypair, rpc } = cmd.opts();
+
+  await setClusterConfig(env, keypair, rpc);
+
+  await registerReferrer();
+});
+
+programCommand("claim-creator-fees")
+  .option("-t, --token <string>", "token address")
+  .action(async (directory, cmd) => {
+    const { env, keypair, rpc, token } = cmd.opts();
+
+    await setClusterConfig(env, keypair, rpc);
+
+    if (token === undefined) {
+      console.log("Error token address");
+      return;
+    }
+
+    await claimCreatorFees(new PublicKey(token));
+  });
+
+programCommand("propose-creator")
+  .option("-t, --token <string>", "token address")
+  .option("-k, --key <string>", "proposed creator address")
+  .action(async (directory, cmd) => {
+    const { env, keypair, rpc, token, key } = cmd.opts();
+
+    await setClusterConfig(env, keypair, rpc);
+
+    if (token === undefined || key === undefined) {
+      console.log("Error token or creator address");
+      return;
+    }
+
+    await proposeCreator(new PublicKey(token), new PublicKey(key));
+  });
+
+programCommand("accept-creator")
+  .option("-t, --token <string>", "token address")
+  .action(async (directory, cmd) => {
+    const { env, keypair, rpc, token } = cmd.opts();
+
+    await setClusterConfig(env, keypair, rpc);
+
+    if (token === undefined) {
+      console.log("Error token address");
+      return;
+    }
+
+    await acceptCreator(new PublicKey(token));
+  });
+
+programCommand("cancel-creator")
+  .option("-t, --token <string>", "token address")
+  .action(async (directory, cmd) => {
+    const { env, keypair, rpc, token } = cmd.opts();
+
+    await setClusterConfig(env, keypair, rpc);
+
+    if (token === undefined) {
+      console.log("Error token address");
+      return;
+    }
+
+    await cancelCreatorTransfer(new PublicKey(token));
+  });
+
+programCommand("propose-authority")
+  .option("-k, --key <string>", "proposed authority address")
+  .option("-g, --migration", "propose the migration authority instead")
+  .action(async (directory, cmd) => {
+    const { env, keypair, rpc, key, migration } = cmd.opts();
+
+    await setClusterConfig(env, keypair, rpc);
+
+    if (key === undefined) {
+      console.log("Error authority address");
+      return;
+    }
+
+    await proposeAuthority(new PublicKey(key), !!migration);
+  });
+
+programCommand("accept-authority")
+  .option("-g, --migration", "accept the migration authority instead")
+  .action(async (directory, cmd) => {
+    const { env, keypair, rpc, migration } = cmd.opts();
+
+    await setClusterConfig(env, keypair, rpc);
+
+    await acceptAuthority(!!migration);
+  });
+
+programCommand("cancel-authority")
+  .option("-g, --migration", "cancel the migration authority transfer instead")
+  .action(async (directory, cmd) => {
+    const { env, keypair, rpc, migration } = cmd.opts();
+
+    await setClusterConfig(env, keypair, rpc);
+
+    await cancelAuthorityTransfer(!!migration);
+  });
+
 programCommand("swap")
   .option("-t, --token <string>", "token address")
   .option("-a, --amount <number>", "swap amount")
   .option("-s, --style <string>", "0: buy token, 1: sell token")
+  .option("-m, --min <number>", "minimum receive amount (tokens for buys, lamports for sells)")
+  .option("-o, --exact-out", "treat amount as the amount to receive")
+  .option("-x, --max <number>", "maximum pay amount for exact-out swaps (lamports for buys, tokens for sells)")
+  .option("-r, --referrer <string>", "registered referrer paid a slice of the platform fee")
   .action(async (directory, cmd) => {
-    const { env, keypair, rpc, token, amount, style } = cmd.opts();
+    const { env, keypair, rpc, token, amount, style, min, exactOut, max, referrer } = cmd.opts();
+    const referrerKey = referrer === undefined ? null : new PublicKey(referrer);
 
     console.log("Solana Cluster:", env);
     console.log("Keypair Path:", keypair);
@@ -75,7 +372,16 @@ programCommand("swap")
       return;
     }
 
-    await swap(new PublicKey(token), amount, style);
+    if (exactOut) {
+      if (max === undefined) {
+        console.log("Error maximum pay amount");
+        return;
+      }
+      await swapExactOut(new PublicKey(token), amount, style, max, referrerKey);
+      return;
+    }
+
+    await swap(new PublicKey(token), amount, style, min ?? 0, referrerKey);
   });
 
 
@@ -8,8 +8,33 @@ import NodeWallet from "@coral-xyz/anchor/dist/cjs/nodewallet";
 
 import { PumpMeteora } from "../target/types/pump_meteora";
 import {
+  acceptAuthorityTx,
+  cancelAuthorityTransferTx,
   createConfigTx,
   createBondingCurveTx,
+  freezeCurveTx,
+  addToWhitelistTx,
+  batchAddToWhitelistTx,
+  removeFromWhitelistTx,
+  setWhitelistEnabledTx,
+  mintTokenProgram,
+  setPresaleAllowanceTx,
+  claimVestedTx,
+  claimCreatorFeesTx,
+  proposeCreatorTx,
+  acceptCreatorTx,
+  cancelCreatorTransferTx,
+  registerReferrerTx,
+  proposeAuthorityTx,
+  setPausedTx,
+  queueConfigChangeTx,
+  settleConfigChangeTx,
+  migrateBondingCurveTx,
+  migrateConfigTx,
+  migrateSolVaultTx,
+  quoteBuy,
+  quoteExactOut,
+  swapExactOutTx,
   swapTx,
 } from "../lib/scripts";
 import { execTx } from "../lib/util";
@@ -100,14 +125,17 @@ export const configProject = async () => {
   const migrationWallet = new PublicKey("DQ8fi6tyN9MPD5bpSpUXxKd9FVRY2WcnoniVEgs6StEW");
   // Create a dummy config object to pass as argument.
   const newConfig = {
+    version: 1, //  always written as the program's current layout
     authority: payer.publicKey,
     migrationAuthority: payer.publicKey,
+    pendingAuthority: PublicKey.default, //  ignored, authorities move through propose / accept
+    pendingMigrationAuthority: PublicKey.default,
     teamWallet: teamWallet,
     migrationWallet: migrationWallet,
     initBondingCurve: new BN(TEST_INIT_BONDING_CURVE),
-    platformBuyFee: 0.69, // Example fee: 0.69%
-    platformSellFee: 0.69, // Example fee: 0.69%
-    platformMigrationFee: 0.69, //  Example fee: 0.69%
+    platformBuyFeeBps: 69, // Example fee: 0.69%
+    platformSellFeeBps: 69, // Example fee: 0.69%
+    platformMigrationFeeBps: 69, //  Example fee: 0.69%
     lamportAmountConfig: {
       range: { min: new BN(15_000_000_000), max: new BN(20_000_000_000) },
     },
@@ -122,7 +150,17 @@ export const configProject = async () => {
     initialMeteoraSolAmount: new BN(TEST_INITIAL_METEORA_SOL_AMOUNT),
 
     curveLimit: new BN(62_000_000_000), //  Example limit: 42 SOL
-    initialized: false,
+    completeOnCurveLimit: false, //  complete on token exhaustion instead of curveLimit
+    allowedCurveKinds: 1, //  constant product only
+    paused: 0, //  new configs always start unpaused
+    configChangeDelay: new BN(0), //  seconds fee and wallet changes wait once queued
+    initialized: false, //  set by the program, `config` only runs once
+    teamVestingCliff: new BN(0), //  seconds before any of the team share unlocks
+    teamVestingDuration: new BN(0), //  seconds the team share unlocks over, linearly
+    whitelistEnabled: false, //  only whitelisted creators can launch when set
+    creatorFeeBps: 0, //  charged on top of the platform fees, accrues to the curve's creator
+    referralShareBps: 0, //  slice of the platform fee paid to referrers, in bps of that fee
+    reserved: new Array(43).fill(0),
   };
 
   const tx = await createConfigTx(
@@ -161,24 +199,215 @@ export const createBondingCurve = async () => {
   await execTx(tx, solConnection, payer);
 };
 
+export const migrateConfig = async () => {
+  const tx = await migrateConfigTx(payer.publicKey, solConnection, program);
+
+  await execTx(tx, solConnection, payer);
+};
+
+//  queues the change, it applies through `settleConfigChange` once the delay has passed
+export const setFees = async (
+  buyFeeBps: number,
+  sellFeeBps: number,
+  migrationFeeBps: number,
+  creatorFeeBps: number,
+  referralShareBps: number
+) => {
+  const tx = await queueConfigChangeTx(
+    payer.publicKey,
+    {
+      fees: {
+        0: {
+          platformBuyFeeBps: buyFeeBps,
+          platformSellFeeBps: sellFeeBps,
+          platformMigrationFeeBps: migrationFeeBps,
+          creatorFeeBps,
+          referralShareBps,
+        },
+      },
+    },
+    solConnection,
+    program
+  );
+
+  await execTx(tx, solConnection, payer);
+};
+
+export const settleConfigChange = async (cancel: boolean) => {
+  const tx = await settleConfigChangeTx(payer.publicKey, cancel, solConnection, program);
+
+  await execTx(tx, solConnection, payer);
+};
+
+export const setPaused = async (flags: number, paused: boolean) => {
+  const tx = await setPausedTx(payer.publicKey, flags, paused, solConnection, program);
+
+  await execTx(tx, solConnection, payer);
+};
+
+export const freezeCurve = async (token: PublicKey, frozen: boolean) => {
+  const tx = await freezeCurveTx(payer.publicKey, token, frozen, solConnection, program);
+
+  await execTx(tx, solConnection, payer);
+};
+
+export const setWhitelistEnabled = async (enabled: boolean) => {
+  const tx = await setWhitelistEnabledTx(payer.publicKey, enabled, solConnection, program);
+
+  await execTx(tx, solConnection, payer);
+};
+
+export const updateWhitelist = async (creators: PublicKey[], remove: boolean) => {
+  const tx = remove
+    ? await removeFromWhitelistTx(payer.publicKey, creators[0], solConnection, program)
+    : creators.length === 1
+    ? await addToWhitelistTx(payer.publicKey, creators[0], solConnection, program)
+    : await batchAddToWhitelistTx(payer.publicKey, creators, solConnection, program);
+
+  await execTx(tx, solConnection, payer);
+};
+
+export const setPresaleAllowance = async (token: PublicKey, wallet: PublicKey, cap: number) => {
+  const tx = await setPresaleAllowanceTx(
+    payer.publicKey,
+    token,
+    wallet,
+    cap,
+    solConnection,
+    program
+  );
+
+  await execTx(tx, solConnection, payer);
+};
+
+//  run with the team wallet keypair
+export const claimVested = async (token: PublicKey) => {
+  const tx = await claimVestedTx(payer.publicKey, token, solConnection, program);
+
+  await execTx(tx, solConnection, payer);
+};
+
+export const claimCreatorFees = async (token: PublicKey) => {
+  const tx = await claimCreatorFeesTx(payer.publicKey, token, solConnection, program);
+
+  await execTx(tx, solConnection, payer);
+};
+
+export const proposeCreator = async (token: PublicKey, newCreator: PublicKey) => {
+  const tx = await proposeCreatorTx(payer.publicKey, token, newCreator, solConnection, program);
+
+  await execTx(tx, solConnection, payer);
+};
+
+export const acceptCreator = async (token: PublicKey) => {
+  const tx = await acceptCreatorTx(payer.publicKey, token, solConnection, program);
+
+  await execTx(tx, solConnection, payer);
+};
+
+export const cancelCreatorTransfer = async (token: PublicKey) => {
+  const tx = await cancelCreatorTransferTx(payer.publicKey, token, solConnection, program);
+
+  await execTx(tx, solConnection, payer);
+};
+
+export const proposeAuthority = async (newAuthority: PublicKey, migration: boolean) => {
+  const tx = await proposeAuthorityTx(
+    payer.publicKey,
+    newAuthority,
+    migration,
+    solConnection,
+    program
+  );
+
+  await execTx(tx, solConnection, payer);
+};
+
+export const acceptAuthority = async (migration: boolean) => {
+  const tx = await acceptAuthorityTx(payer.publicKey, migration, solConnection, program);
+
+  await execTx(tx, solConnection, payer);
+};
+
+export const cancelAuthorityTransfer = async (migration: boolean) => {
+  const tx = await cancelAuthorityTransferTx(payer.publicKey, migration, solConnection, program);
+
+  await execTx(tx, solConnection, payer);
+};
+
+export const migrateBondingCurve = async (token: PublicKey) => {
+  const tx = await migrateBondingCurveTx(
+    payer.publicKey,
+    token,
+    solConnection,
+    program
+  );
+
+  await execTx(tx, solConnection, payer);
+};
+
+export const migrateSolVault = async (token: PublicKey) => {
+  const tx = await migrateSolVaultTx(
+    payer.publicKey,
+    token,
+    solConnection,
+    program
+  );
+
+  await execTx(tx, solConnection, payer);
+};
+
 export const swap = async (
   token: PublicKey,
 
   amount: number,
-  style: number
+  style: number,
+  minimumReceiveAmount: number = 0,
+  referrer: PublicKey | null = null
 ) => {
   const tx = await swapTx(
     payer.publicKey,
     token,
     amount,
     style,
+    minimumReceiveAmount,
     solConnection,
-    program
+    program,
+    referrer
+  );
+
+  await execTx(tx, solConnection, payer);
+};
+
+//  `amount` is the tokens to receive for buys and the lamports to receive for sells
+export const swapExactOut = async (
+  token: PublicKey,
+
+  amount: number,
+  style: number,
+  maximumPayAmount: number,
+  referrer: PublicKey | null = null
+) => {
+  const tx = await swapExactOutTx(
+    payer.publicKey,
+    token,
+    amount,
+    style,
+    maximumPayAmount,
+    solConnection,
+    program,
+    referrer
   );
 
   await execTx(tx, solConnection, payer);
 };
 
+export const registerReferrer = async () => {
+  const tx = await registerReferrerTx(payer.publicKey, solConnection, program);
+
+  await execTx(tx, solConnection, payer);
+};
+
 export const METEORA_CONFIG = publicKey("BdfD7rrTZEWmf8UbEBPVpvM3wUqyrR8swjAy5SNT8gJ2");
 
 export const initMigrationTx = async (mint: string) => {
@@ -378,6 +607,7 @@ export const initMigrationTx = async (mint: string) => {
           rent: SYSVAR_RENT_PUBKEY,
           metadataProgram: METAPLEX_PROGRAM,
           vaultProgram: vaultProgram.programId,
+          tokenMintProgram: await mintTokenProgram(tokenBMint, solConnection),
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           meteoraProgram: PROGRAM_ID,
           eventAuthority,
@@ -501,90 +731,26 @@ export const initMigrationTx = async (mint: string) => {
   return lockPoolId;
 }
 
-const calcPrice = (
-  virtualTokenReserves: BN,
-  virtualSolReserves: BN
-): number => {
-  if (virtualSolReserves.isZero()) {
-    throw new Error("Division by zero: virtualSolReserves is zero.");
-  }
-
-  return virtualSolReserves.toNumber() / virtualTokenReserves.toNumber() / 1000;
-};
-
 export const getCurrentPrice = async (mint: string) =>{
   const tokenBMint = new PublicKey(mint);
   const bondingCurvePda = PublicKey.findProgramAddressSync([Buffer.from(SEED_BONDING_CURVE), tokenBMint.toBytes()], program.programId)[0];
   const bondingCurve = await program.account.bondingCurve.fetch(bondingCurvePda);
-  const curveData = {
-    virtualSolReserves: bondingCurve.virtualSolReserves,
-    virtualTokenReserves: bondingCurve.virtualTokenReserves,
-    realSolReserves: bondingCurve.realSolReserves,
-    realTokenReserves: bondingCurve.realTokenReserves,
-  };
-  console.log(curveData);
-  const currentPrice = calcPrice(curveData.virtualTokenReserves, curveData.virtualSolReserves);
+
+  //  SOL the curve charges for one whole token, fee excluded
+  const quote = await quoteExactOut(tokenBMint, 10 ** bondingCurve.decimals, 0, program);
+  const currentPrice = quote.solAmount.toNumber() / 10 ** 9;
   console.log("Current Price:", currentPrice);
   return currentPrice;
 }
 
-const SCALE_UP = new BN(1_000_000_000);  // Scale tokens to 9 decimals
-const SCALE_DOWN = new BN(1_000_000);    // Convert back to 6 decimals
-
-const calculateTokensOut = (
-  virtualSolReserves: BN,
-  virtualTokenReserves: BN,
-  solAmount: BN
-): BN | null => {
-  // Convert token reserves to 9 decimal places
-  const currentSol = virtualSolReserves;
-  const currentTokens = virtualTokenReserves.mul(SCALE_UP).div(SCALE_DOWN);
-
-  if (currentTokens.isZero()) return null; // Avoid division by zero
-
-  // Calculate new reserves using constant product formula
-  const newSol = currentSol.add(solAmount);
-  const newTokens = currentSol.mul(currentTokens).div(newSol);
-
-  // Tokens to be received
-  let tokensOut = currentTokens.sub(newTokens);
-
-  // Convert back to 6 decimal places
-  tokensOut = tokensOut.mul(SCALE_DOWN).div(SCALE_UP);
-
-  return tokensOut;
-};
-
-const solAmountToBN = (solAmount: number): BN => {
-  return new BN(solAmount); // Scale to 9 decimals
-};
-
 export const calculateSwap = async (mint: string, solAmount: number) =>{
   if (solAmount == 0) {
     return null;
   }
-  const tokenBMint = new PublicKey(mint);
-  const bondingCurvePda = PublicKey.findProgramAddressSync([Buffer.from(SEED_BONDING_CURVE), tokenBMint.toBytes()], program.programId)[0];
-  const bondingCurve = await program.account.bondingCurve.fetch(bondingCurvePda);
-  const curveData = {
-    virtualSolReserves: bondingCurve.virtualSolReserves,
-    virtualTokenReserves: bondingCurve.virtualTokenReserves,
-    realSolReserves: bondingCurve.realSolReserves,
-    realTokenReserves: bondingCurve.realTokenReserves,
-  };
-  console.log(curveData);
-
-  solAmount -= solAmount * 0.0069; // Decrease by 0.69%
-  console.log("Updated SOL Amount:", solAmount);
-  const solAmountBN = solAmountToBN(solAmount);
-  console.log(solAmountBN.toString());
-  let tokensOut = calculateTokensOut(curveData.virtualSolReserves, curveData.virtualTokenReserves, solAmountBN);
-  
-  if (tokensOut >= curveData.realTokenReserves){
-    console.log("realTokenReserves limit:", tokensOut?.toString(), curveData.realTokenReserves.toString());
-    tokensOut = curveData.realTokenReserves;
-  }
-  console.log("Tokens Out:", tokensOut?.toString());
+  const quote = await quoteBuy(new PublicKey(mint), solAmount, program);
+  console.log("Fee:", quote.feeAmount.toString());
+  console.log("Tokens Out:", quote.tokenAmount.toString());
+  return quote.tokenAmount;
 }
 
 function sleep(ms: number): Promise<void> {
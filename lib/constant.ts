@@ -3,6 +3,10 @@ import { Cluster, PublicKey } from "@solana/web3.js";
 
 export const SEED_CONFIG = "config";
 export const SEED_BONDING_CURVE = "bonding_curve";
+export const SEED_WHITELIST = "whitelist";
+export const SEED_PRESALE = "presale";
+export const SEED_REFERRER = "referrer";
+export const SEED_TEAM_VESTING = "team_vesting";
 
 export const TEST_NAME = "asaasin 1";
 export const TEST_SYMBOL = "sin 1";
@@ -11,7 +15,8 @@ export const TEST_URI =
 export const TEST_VIRTUAL_RESERVES = 15_000_000_000;
 export const TEST_TOKEN_SUPPLY = 1_000_000_000_000_000;
 export const TEST_DECIMALS = 6;
-export const TEST_INIT_BONDING_CURVE = 80;
+//  the example reserves below take the whole supply, lower them with this to vest a team share
+export const TEST_INIT_BONDING_CURVE = 100;
 export const TEST_INITIAL_VIRTUAL_TOKEN_RESERVES = 1_073_000_000_000_000;
 export const TEST_INITIAL_VIRTUAL_SOL_RESERVES = 15_000_000_000;
 export const TEST_INITIAL_REAL_TOKEN_RESERVES = 793_100_000_000_000;
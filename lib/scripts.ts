@@ -16,13 +16,24 @@ import {
   marketProgram,
   SEED_BONDING_CURVE,
   SEED_CONFIG,
+  SEED_PRESALE,
+  SEED_REFERRER,
+  SEED_TEAM_VESTING,
+  SEED_WHITELIST,
 } from "./constant";
 import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
   NATIVE_MINT,
   TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 
+//  atas are derived with the token program that owns the mint
+export const mintTokenProgram = async (token: PublicKey, connection: Connection) => {
+  const info = await connection.getAccountInfo(token);
+  return info ? info.owner : TOKEN_PROGRAM_ID;
+};
+
 export const createConfigTx = async (
   admin: PublicKey,
 
@@ -52,6 +63,353 @@ export const createConfigTx = async (
   return tx;
 };
 
+export const migrateConfigTx = async (
+  admin: PublicKey,
+
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const tx = await program.methods
+    .migrateConfig()
+    .accounts({
+      authority: admin,
+    })
+    .transaction();
+
+  tx.feePayer = admin;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+export const migrateBondingCurveTx = async (
+  admin: PublicKey,
+  token: PublicKey,
+
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const tx = await program.methods
+    .migrateBondingCurve()
+    .accounts({
+      authority: admin,
+      tokenMint: token,
+    })
+    .transaction();
+
+  tx.feePayer = admin;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+export const migrateSolVaultTx = async (
+  admin: PublicKey,
+  token: PublicKey,
+
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const tx = await program.methods
+    .migrateSolVault()
+    .accounts({
+      authority: admin,
+      tokenMint: token,
+    })
+    .transaction();
+
+  tx.feePayer = admin;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+//  `params` matches the rust struct of the group, e.g. `{ curveLimit, ... }` for curve params
+export const updateConfigTx = async (
+  admin: PublicKey,
+  group: "curveParams" | "launchLimits",
+  params: any,
+
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const method = {
+    curveParams: () => program.methods.setCurveParams(params),
+    launchLimits: () => program.methods.setLaunchLimits(params),
+  }[group]();
+
+  const tx = await method
+    .accounts({
+      authority: admin,
+    })
+    .transaction();
+
+  tx.feePayer = admin;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+//  `change` is a timelocked `ConfigUpdate`, e.g. `{ fees: { 0: { platformBuyFeeBps, ... } } }`
+export const queueConfigChangeTx = async (
+  admin: PublicKey,
+  change: any,
+
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const tx = await program.methods
+    .queueConfigChange(change)
+    .accounts({
+      authority: admin,
+    })
+    .transaction();
+
+  tx.feePayer = admin;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+//  `cancel` drops the queued change instead of applying it
+export const settleConfigChangeTx = async (
+  admin: PublicKey,
+  cancel: boolean,
+
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const method = cancel
+    ? program.methods.cancelConfigChange()
+    : program.methods.executeConfigChange();
+
+  const tx = await method
+    .accounts({
+      authority: admin,
+    })
+    .transaction();
+
+  tx.feePayer = admin;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+//  `flags` is a mask of 1: create, 2: buy, 4: sell, 8: migrate
+export const setPausedTx = async (
+  admin: PublicKey,
+  flags: number,
+  paused: boolean,
+
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const method = paused
+    ? program.methods.pause(flags)
+    : program.methods.unpause(flags);
+
+  const tx = await method
+    .accounts({
+      authority: admin,
+    })
+    .transaction();
+
+  tx.feePayer = admin;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+export const freezeCurveTx = async (
+  admin: PublicKey,
+  token: PublicKey,
+  frozen: boolean,
+
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const method = frozen
+    ? program.methods.freezeCurve()
+    : program.methods.unfreezeCurve();
+
+  const tx = await method
+    .accounts({
+      authority: admin,
+      tokenMint: token,
+    })
+    .transaction();
+
+  tx.feePayer = admin;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+export const whitelistPda = (creator: PublicKey, program: Program<PumpMeteora>) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from(SEED_WHITELIST), creator.toBytes()],
+    program.programId
+  )[0];
+
+export const setWhitelistEnabledTx = async (
+  admin: PublicKey,
+  enabled: boolean,
+
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const tx = await program.methods
+    .setWhitelistEnabled(enabled)
+    .accounts({
+      authority: admin,
+    })
+    .transaction();
+
+  tx.feePayer = admin;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+export const addToWhitelistTx = async (
+  admin: PublicKey,
+  creator: PublicKey,
+
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const tx = await program.methods
+    .addToWhitelist(creator)
+    .accounts({
+      authority: admin,
+    })
+    .transaction();
+
+  tx.feePayer = admin;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+export const removeFromWhitelistTx = async (
+  admin: PublicKey,
+  creator: PublicKey,
+
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const tx = await program.methods
+    .removeFromWhitelist(creator)
+    .accounts({
+      authority: admin,
+    })
+    .transaction();
+
+  tx.feePayer = admin;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+//  at most 16 creators per transaction, creators already whitelisted are skipped
+export const batchAddToWhitelistTx = async (
+  admin: PublicKey,
+  creators: PublicKey[],
+
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const tx = await program.methods
+    .batchAddToWhitelist(creators)
+    .accounts({
+      authority: admin,
+    })
+    .remainingAccounts(
+      creators.map((creator) => ({
+        pubkey: whitelistPda(creator, program),
+        isSigner: false,
+        isWritable: true,
+      }))
+    )
+    .transaction();
+
+  tx.feePayer = admin;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+//  `migration` selects `migration_authority` instead of `authority`
+export const proposeAuthorityTx = async (
+  admin: PublicKey,
+  newAuthority: PublicKey,
+  migration: boolean,
+
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const method = migration
+    ? program.methods.proposeMigrationAuthority(newAuthority)
+    : program.methods.proposeAuthority(newAuthority);
+
+  const tx = await method
+    .accounts({
+      authority: admin,
+    })
+    .transaction();
+
+  tx.feePayer = admin;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+export const acceptAuthorityTx = async (
+  newAuthority: PublicKey,
+  migration: boolean,
+
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const method = migration
+    ? program.methods.acceptMigrationAuthority()
+    : program.methods.acceptAuthority();
+
+  const tx = await method
+    .accounts({
+      newAuthority,
+    })
+    .transaction();
+
+  tx.feePayer = newAuthority;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+export const cancelAuthorityTransferTx = async (
+  admin: PublicKey,
+  migration: boolean,
+
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const method = migration
+    ? program.methods.cancelMigrationAuthorityTransfer()
+    : program.methods.cancelAuthorityTransfer();
+
+  const tx = await method
+    .accounts({
+      authority: admin,
+    })
+    .transaction();
+
+  tx.feePayer = admin;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
 export const createBondingCurveTx = async (
   decimal: number,
   supply: number,
@@ -63,12 +421,40 @@ export const createBondingCurveTx = async (
   user: PublicKey,
   teamWallet: PublicKey,
   connection: Connection,
-  program: Program<PumpMeteora>
+  program: Program<PumpMeteora>,
+  curveKind: any = { constantProduct: {} },
+  //  unix seconds, 0 opens trading right away and skips the presale
+  startTime: number = 0,
+  presaleEndTime: number = 0,
+  //  lamports the creator buys with in the same transaction, fee included, 0 skips it
+  initialBuyLamports: number = 0,
+  maxTokensOut: number = Number.MAX_SAFE_INTEGER
 ) => {
   const tokenKp = Keypair.generate();
 
   console.log("token address: ", tokenKp.publicKey.toBase58());
 
+  //  the whitelist entry is only checked while the whitelist is enabled
+  const [configPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from(SEED_CONFIG)],
+    program.programId
+  );
+  const config = await program.account.config.fetch(configPda);
+  const whitelistEntry = config.whitelistEnabled ? whitelistPda(user, program) : null;
+
+  //  the vesting escrow is only created when part of the supply is left to the team
+  const curveShareBps = new BN(Math.round(config.initBondingCurve * 100));
+  const teamTokens = new BN(supply).sub(new BN(supply).mul(curveShareBps).divn(10_000));
+  const teamVesting = teamTokens.isZero()
+    ? null
+    : PublicKey.findProgramAddressSync(
+        [Buffer.from(SEED_TEAM_VESTING), tokenKp.publicKey.toBytes()],
+        program.programId
+      )[0];
+  const teamVestingTokenAccount = teamVesting
+    ? getAssociatedTokenAddressSync(tokenKp.publicKey, teamVesting, true, TOKEN_PROGRAM_ID)
+    : null;
+
   // Send the transaction to launch a token
   const tx = await program.methods
     .createBondingCurve(
@@ -76,6 +462,11 @@ export const createBondingCurveTx = async (
       decimal,
       new BN(supply),
       new BN(reserve),
+      curveKind,
+      { startTime: new BN(startTime), presaleEndTime: new BN(presaleEndTime) },
+      initialBuyLamports > 0
+        ? { lamports: new BN(initialBuyLamports), maxTokensOut: new BN(maxTokensOut) }
+        : null,
 
       //  metadata
       name,
@@ -86,6 +477,10 @@ export const createBondingCurveTx = async (
       creator: user,
       token: tokenKp.publicKey,
       teamWallet,
+      whitelistEntry,
+      teamVesting,
+      teamVestingTokenAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
     })
     .transaction();
 
@@ -97,15 +492,245 @@ export const createBondingCurveTx = async (
   return tx;
 };
 
+export const presaleAllowancePda = (
+  token: PublicKey,
+  wallet: PublicKey,
+  program: Program<PumpMeteora>
+) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from(SEED_PRESALE), token.toBytes(), wallet.toBytes()],
+    program.programId
+  )[0];
+
+//  buys during a presale need the wallet's allowance, it is left out when there is none
+const presaleAllowanceIfAny = async (
+  token: PublicKey,
+  wallet: PublicKey,
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const allowance = presaleAllowancePda(token, wallet, program);
+  return (await connection.getAccountInfo(allowance)) ? allowance : null;
+};
+
+//  set `cap` to 0 to stop a wallet's presale buys
+export const setPresaleAllowanceTx = async (
+  creator: PublicKey,
+  token: PublicKey,
+  wallet: PublicKey,
+  cap: number,
+
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const tx = await program.methods
+    .setPresaleAllowance(wallet, new BN(cap))
+    .accounts({
+      creator,
+      tokenMint: token,
+    })
+    .transaction();
+
+  tx.feePayer = creator;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+export const claimVestedTx = async (
+  teamWallet: PublicKey,
+  token: PublicKey,
+
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const tx = await program.methods
+    .claimVested()
+    .accounts({
+      teamWallet,
+      tokenMint: token,
+      tokenProgram: await mintTokenProgram(token, connection),
+    })
+    .transaction();
+
+  tx.feePayer = teamWallet;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+export const claimCreatorFeesTx = async (
+  creator: PublicKey,
+  token: PublicKey,
+
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const tx = await program.methods
+    .claimCreatorFees()
+    .accounts({
+      creator,
+      tokenMint: token,
+    })
+    .transaction();
+
+  tx.feePayer = creator;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+//  the new creator also takes over the unclaimed fees, the presale allowlist and the lp lock share,
+//  once they accept
+export const proposeCreatorTx = async (
+  creator: PublicKey,
+  token: PublicKey,
+  newCreator: PublicKey,
+
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const tx = await program.methods
+    .proposeCreator(newCreator)
+    .accounts({
+      creator,
+      tokenMint: token,
+    })
+    .transaction();
+
+  tx.feePayer = creator;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+export const acceptCreatorTx = async (
+  newCreator: PublicKey,
+  token: PublicKey,
+
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const tx = await program.methods
+    .acceptCreator()
+    .accounts({
+      newCreator,
+      tokenMint: token,
+    })
+    .transaction();
+
+  tx.feePayer = newCreator;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+export const cancelCreatorTransferTx = async (
+  creator: PublicKey,
+  token: PublicKey,
+
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const tx = await program.methods
+    .cancelCreatorTransfer()
+    .accounts({
+      creator,
+      tokenMint: token,
+    })
+    .transaction();
+
+  tx.feePayer = creator;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+export const referrerStatsPda = (referrer: PublicKey, program: Program<PumpMeteora>) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from(SEED_REFERRER), referrer.toBytes()],
+    program.programId
+  )[0];
+
+//  once registered, `referrer` can be passed to swaps to earn its slice of the platform fee
+export const registerReferrerTx = async (
+  referrer: PublicKey,
+
+  connection: Connection,
+  program: Program<PumpMeteora>
+) => {
+  const tx = await program.methods
+    .registerReferrer()
+    .accounts({
+      referrer,
+    })
+    .transaction();
+
+  tx.feePayer = referrer;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+//  quotes run the on-chain curve math through simulation, nothing is signed or sent.
+//  passing the trade's `referrer` quotes its slice of the platform fee as well.
+const quoteAccounts = (
+  token: PublicKey,
+  referrer: PublicKey | null,
+  program: Program<PumpMeteora>
+) => ({
+  tokenMint: token,
+  referrerStats: referrer ? referrerStatsPda(referrer, program) : null,
+});
+
+export const quoteBuy = async (
+  token: PublicKey,
+  solAmount: number,
+  program: Program<PumpMeteora>,
+  referrer: PublicKey | null = null
+) => {
+  return await program.methods
+    .quoteBuy(new BN(solAmount))
+    .accounts(quoteAccounts(token, referrer, program))
+    .view();
+};
+
+export const quoteSell = async (
+  token: PublicKey,
+  tokenAmount: number,
+  program: Program<PumpMeteora>,
+  referrer: PublicKey | null = null
+) => {
+  return await program.methods
+    .quoteSell(new BN(tokenAmount))
+    .accounts(quoteAccounts(token, referrer, program))
+    .view();
+};
+
+export const quoteExactOut = async (
+  token: PublicKey,
+  amount: number,
+  style: number,
+  program: Program<PumpMeteora>,
+  referrer: PublicKey | null = null
+) => {
+  return await program.methods
+    .quoteExactOut(new BN(amount), style == 1 ? { sell: {} } : { buy: {} })
+    .accounts(quoteAccounts(token, referrer, program))
+    .view();
+};
+
 export const swapTx = async (
   user: PublicKey,
   token: PublicKey,
 
   amount: number,
   style: number,
+  minimumReceiveAmount: number,
 
   connection: Connection,
-  program: Program<PumpMeteora>
+  program: Program<PumpMeteora>,
+  referrer: PublicKey | null = null
 ) => {
   const [configPda, _] = PublicKey.findProgramAddressSync(
     [Buffer.from(SEED_CONFIG)],
@@ -113,12 +738,67 @@ export const swapTx = async (
   );
   const configAccount = await program.account.config.fetch(configPda);
 
-  const tx = await program.methods
-    .swap(new BN(amount), style, new BN(amount))
+  const method =
+    style == 1
+      ? program.methods.sell(new BN(amount), new BN(minimumReceiveAmount))
+      : program.methods.buy(new BN(amount), new BN(minimumReceiveAmount));
+
+  const presaleAllowance =
+    style == 1 ? undefined : await presaleAllowanceIfAny(token, user, connection, program);
+
+  const tx = await method
+    .accounts({
+      teamWallet: configAccount.teamWallet,
+      user,
+      tokenMint: token,
+      tokenProgram: await mintTokenProgram(token, connection),
+      referrer,
+      referrerStats: referrer ? referrerStatsPda(referrer, program) : null,
+      ...(style == 1 ? {} : { presaleAllowance }),
+    })
+    .transaction();
+
+  tx.feePayer = user;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+export const swapExactOutTx = async (
+  user: PublicKey,
+  token: PublicKey,
+
+  amount: number,
+  style: number,
+  maximumPayAmount: number,
+
+  connection: Connection,
+  program: Program<PumpMeteora>,
+  referrer: PublicKey | null = null
+) => {
+  const [configPda, _] = PublicKey.findProgramAddressSync(
+    [Buffer.from(SEED_CONFIG)],
+    program.programId
+  );
+  const configAccount = await program.account.config.fetch(configPda);
+
+  const method =
+    style == 1
+      ? program.methods.sellExactOut(new BN(amount), new BN(maximumPayAmount))
+      : program.methods.buyExactOut(new BN(amount), new BN(maximumPayAmount));
+
+  const presaleAllowance =
+    style == 1 ? undefined : await presaleAllowanceIfAny(token, user, connection, program);
+
+  const tx = await method
     .accounts({
       teamWallet: configAccount.teamWallet,
       user,
       tokenMint: token,
+      tokenProgram: await mintTokenProgram(token, connection),
+      referrer,
+      referrerStats: referrer ? referrerStatsPda(referrer, program) : null,
+      ...(style == 1 ? {} : { presaleAllowance }),
     })
     .transaction();
 
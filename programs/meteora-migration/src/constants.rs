@@ -1,12 +1,19 @@
 pub const CONFIG: &str = "config";
 pub const GLOBAL: &str = "global";
 pub const BONDING_CURVE: &str = "bonding_curve";
+pub const SOL_VAULT: &str = "sol_vault";
+pub const PENDING_CONFIG_CHANGE: &str = "pending_config_change";
+pub const WHITELIST: &str = "whitelist";
+pub const PRESALE: &str = "presale";
+pub const TEAM_VESTING: &str = "team_vesting";
+pub const REFERRER: &str = "referrer";
+//  most creators `batch_add_to_whitelist` takes in one transaction
+pub const MAX_WHITELIST_BATCH: usize = 16;
 pub const METADATA: &str = "metadata";
 pub const LAMPORT_DECIMALS: u8 = 9;
+//  token amounts in `Config` are expressed with these decimals and rescaled per mint
+pub const CONFIG_TOKEN_DECIMALS: u8 = 6;
 pub const TOKEN_LAUNCH: &str = "token_launch";
-pub const CONSTANT: f64 = 23_625_000_000.0;
-pub const VIRTUAL_SOL: f64 = 20.0;
-pub const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0; // Convert SOL to lamports
 pub const INITIAL_VIRTUAL_TOKEN_RESERVES: u64 = 1_073_000_000_000_000;
 pub const INITIAL_VIRTUAL_SOL_RESERVES: u64 = 15_000_000_000;
 pub const INITIAL_REAL_TOKEN_RESERVES: u64 = 793_100_000_000_000;
@@ -19,3 +26,4 @@ pub const METEORA_VAULT_PROGRAM_KEY: &str = "24Uqj9JCLxUeoC3hGfh5W3s9FM9uCHDS2SG
 pub const QUOTE_MINT: &str = "So11111111111111111111111111111111111111112";
 
 pub const TOKEN_VAULT_SEED: &str = "token_vault";
+pub const POOL_CREATION_RESERVE_LAMPORTS: u64 = 40_000_000;
@@ -87,4 +87,124 @@ pub enum ContractError {
 
     #[msg("Arithmetic Error")]
     ArithmeticError,
+
+    #[msg("Bonding curve SOL vault is not initialized")]
+    SolVaultNotInitialized,
+
+    #[msg("Bonding curve SOL vault is already initialized")]
+    SolVaultAlreadyInitialized,
+
+    #[msg("Invalid curve parameters")]
+    InvalidCurveParams,
+
+    #[msg("Curve kind is not allowed")]
+    CurveKindNotAllowed,
+
+    #[msg("Pay amount is too large")]
+    PayAmountTooLarge,
+
+    #[msg("Invalid swap direction")]
+    InvalidDirection,
+
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+
+    #[msg("Fee can not be more than 100%")]
+    FeeTooHigh,
+
+    #[msg("Wallet can not be the default address")]
+    InvalidWallet,
+
+    #[msg("Init bonding curve percentage must be above 0 and at most 100")]
+    InvalidInitBondingCurve,
+
+    #[msg("Allowed curve kinds must include at least one known kind")]
+    InvalidAllowedCurveKinds,
+
+    #[msg("Curve limit must be set to complete curves on it")]
+    InvalidCurveLimit,
+
+    #[msg("Real token reserves must be positive and below the virtual token reserves")]
+    InvalidReserves,
+
+    #[msg("Curve and meteora token reserves exceed every allowed token supply")]
+    ReservesExceedSupply,
+
+    #[msg("Meteora SOL amount is more than a completed curve raises")]
+    MeteoraSolExceedsRaise,
+
+    #[msg("Launch limits can not be satisfied by any value")]
+    InvalidLaunchLimits,
+
+    #[msg("This action is paused")]
+    Paused,
+
+    #[msg("Invalid pause flags")]
+    InvalidPauseFlags,
+
+    #[msg("Bonding curve is frozen")]
+    CurveFrozen,
+
+    #[msg("Config change delay can not be negative")]
+    InvalidConfigChangeDelay,
+
+    #[msg("Fee, wallet and delay changes must be queued")]
+    TimelockRequired,
+
+    #[msg("Only fee, wallet and delay changes can be queued")]
+    NotTimelocked,
+
+    #[msg("Config change delay has not passed yet")]
+    ConfigChangeNotReady,
+
+    #[msg("Account must be migrated to the current layout first")]
+    AccountNeedsMigration,
+
+    #[msg("Account is already in the current layout")]
+    AccountAlreadyMigrated,
+
+    #[msg("Too many creators in one whitelist batch")]
+    WhitelistBatchTooLarge,
+
+    #[msg("Presale must end after trading starts")]
+    InvalidLaunchSchedule,
+
+    #[msg("Presale buys would exceed this wallet's cap")]
+    PresaleCapExceeded,
+
+    #[msg("Vesting cliff must be between 0 and the vesting duration")]
+    InvalidVestingSchedule,
+
+    #[msg("No vested tokens to claim")]
+    NothingToClaim,
+
+    #[msg("No creator fees to claim")]
+    NoCreatorFees,
+
+    #[msg("Creator can not be the default address")]
+    InvalidCreator,
+
+    #[msg("Referrer does not match its stats account")]
+    IncorrectReferrer,
+
+    #[msg("Traders can not refer themselves")]
+    SelfReferral,
+
+    #[msg("Account is not a bonding curve")]
+    IncorrectBondingCurveAccount,
+
+    #[msg("Team vesting accounts must be passed exactly when the launch has a team share")]
+    InvalidTeamVesting,
+
+    #[msg("Curves of this token program can not migrate to meteora yet")]
+    TokenProgramNotMigratable,
+
+    #[msg("Curve has already migrated to meteora")]
+    CurveAlreadyMigrated,
+
+    #[msg("Config is already initialized, change it through the set instructions")]
+    ConfigAlreadyInitialized,
+
+    #[msg("No creator transfer is pending")]
+    NoPendingCreator,
 }
@@ -1,3 +1,4 @@
+use crate::state::{bondingcurve::Direction, config::{AuthorityRole, ConfigUpdate}};
 use anchor_lang::prelude::*;
 
 #[event]
@@ -12,6 +13,13 @@ pub struct LaunchEvent {
 
     pub reserve_lamport: u64,
     pub reserve_token: u64,
+
+    pub start_time: i64,
+    pub presale_end_time: i64,
+
+    //  `token_supply` split between the curve and the team vesting escrow
+    pub curve_tokens: u64,
+    pub team_tokens: u64,
 }
 
 #[event]
@@ -21,9 +29,16 @@ pub struct SwapEvent {
     pub bonding_curve: Pubkey,
 
     pub amount_in: u64,
-    pub direction: u8,
+    pub direction: Direction,
+    pub exact_out: bool,
     pub minimum_receive_amount: u64,
     pub amount_out: u64,
+    pub fee_amount: u64,
+    //  part of `fee_amount` accrued to the curve's creator
+    pub creator_fee_amount: u64,
+    //  part of `fee_amount` paid to the referrer of the trade, if any
+    pub referrer: Option<Pubkey>,
+    pub referral_fee_amount: u64,
 
     pub virtual_sol_reserves: u64,
     pub virtual_token_reserves: u64,
@@ -34,4 +49,125 @@ pub struct CompleteEvent {
     pub user: Pubkey,
     pub mint: Pubkey,
     pub bonding_curve: Pubkey,
-}
\ No newline at end of file
+}
+
+#[event]
+pub struct AuthorityProposedEvent {
+    pub role: AuthorityRole,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityAcceptedEvent {
+    pub role: AuthorityRole,
+    pub previous_authority: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferCancelledEvent {
+    pub role: AuthorityRole,
+    pub authority: Pubkey,
+    pub cancelled_authority: Pubkey,
+}
+
+#[event]
+pub struct ConfigUpdatedEvent {
+    pub authority: Pubkey,
+    pub old: ConfigUpdate,
+    pub new: ConfigUpdate,
+}
+
+#[event]
+pub struct PausedEvent {
+    pub authority: Pubkey,
+    pub flags: u8,  //  bits newly paused
+    pub paused: u8, //  every paused bit afterwards
+}
+
+#[event]
+pub struct UnpausedEvent {
+    pub authority: Pubkey,
+    pub flags: u8,
+    pub paused: u8,
+}
+
+#[event]
+pub struct CurveFrozenEvent {
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub frozen: bool,
+}
+
+#[event]
+pub struct ConfigChangeQueuedEvent {
+    pub authority: Pubkey,
+    pub change: ConfigUpdate,
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct ConfigChangeCancelledEvent {
+    pub authority: Pubkey,
+    pub change: ConfigUpdate,
+}
+
+#[event]
+pub struct WhitelistUpdatedEvent {
+    pub authority: Pubkey,
+    pub creator: Pubkey,
+    pub whitelisted: bool,
+}
+
+#[event]
+pub struct PresaleAllowanceEvent {
+    pub creator: Pubkey,
+    pub mint: Pubkey,
+    pub wallet: Pubkey,
+    pub cap: u64,
+    pub spent: u64,
+}
+
+#[event]
+pub struct VestedClaimedEvent {
+    pub team_wallet: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub claimed_amount: u64,
+    pub total_amount: u64,
+}
+
+#[event]
+pub struct CreatorFeesClaimedEvent {
+    pub creator: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ReferrerRegisteredEvent {
+    pub referrer: Pubkey,
+}
+
+#[event]
+pub struct CreatorProposedEvent {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub pending_creator: Pubkey,
+}
+
+#[event]
+pub struct CreatorTransferredEvent {
+    pub mint: Pubkey,
+    pub previous_creator: Pubkey,
+    pub creator: Pubkey,
+}
+
+#[event]
+pub struct CreatorTransferCancelledEvent {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub cancelled_creator: Pubkey,
+}
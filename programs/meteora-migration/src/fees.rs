@@ -0,0 +1,191 @@
+//  fixed-point fee math. every fee in the program is expressed in basis points and
+//  rounded up, so the protocol is never undercharged and `fee + net == gross` always holds.
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeSplit {
+    pub fee: u64,
+    pub net: u64,
+}
+
+//  fee owed on `amount`, rounded up. `None` when `fee_bps` is above 100%
+pub fn calculate_fee(amount: u64, fee_bps: u16) -> Option<u64> {
+    if fee_bps as u64 > BPS_DENOMINATOR {
+        return None;
+    }
+
+    let fee = (amount as u128)
+        .checked_mul(fee_bps as u128)?
+        .checked_add(BPS_DENOMINATOR as u128 - 1)?
+        .checked_div(BPS_DENOMINATOR as u128)?;
+
+    fee.try_into().ok()
+}
+
+//  splits a gross amount into the fee and what is left for the user / curve
+pub fn split_fee(gross: u64, fee_bps: u16) -> Option<FeeSplit> {
+    let fee = calculate_fee(gross, fee_bps)?;
+    let net = gross.checked_sub(fee)?;
+
+    Some(FeeSplit { fee, net })
+}
+
+//  smallest gross amount whose net after the fee covers `net`
+pub fn gross_for_net(net: u64, fee_bps: u16) -> Option<u64> {
+    let kept_bps = BPS_DENOMINATOR.checked_sub(fee_bps as u64)?;
+    if kept_bps == 0 {
+        return (net == 0).then_some(0);
+    }
+
+    //  net(gross) == floor(gross * kept / 10_000), so gross == ceil(net * 10_000 / kept)
+    let gross = (net as u128)
+        .checked_mul(BPS_DENOMINATOR as u128)?
+        .checked_add(kept_bps as u128 - 1)?
+        .checked_div(kept_bps as u128)?;
+
+    gross.try_into().ok()
+}
+
+//  share of `fee` owed when only `used` of `total` was consumed, rounded up
+pub fn prorate_fee(fee: u64, used: u64, total: u64) -> Option<u64> {
+    if used > total {
+        return None;
+    }
+    if total == 0 {
+        return Some(0);
+    }
+
+    let prorated = (fee as u128)
+        .checked_mul(used as u128)?
+        .checked_add(total as u128 - 1)?
+        .checked_div(total as u128)?;
+
+    prorated.try_into().ok()
+}
+
+//  part of a fee charged at `total_bps` that belongs to `share_bps` of it, rounded down so
+//  whoever takes the rest of the fee keeps the rounding
+pub fn fee_share(fee: u64, share_bps: u16, total_bps: u16) -> Option<u64> {
+    if share_bps > total_bps {
+        return None;
+    }
+    if total_bps == 0 {
+        return Some(0);
+    }
+
+    let share = (fee as u128)
+        .checked_mul(share_bps as u128)?
+        .checked_div(total_bps as u128)?;
+
+    share.try_into().ok()
+}
+
+//  converts a legacy f64 percentage (e.g. `0.69`) into basis points
+pub fn bps_from_percentage(percentage: f64) -> Option<u16> {
+    if !percentage.is_finite() || !(0.0..=100.0).contains(&percentage) {
+        return None;
+    }
+
+    Some((percentage * 100.0).round() as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AMOUNTS: [u64; 10] = [
+        0,
+        1,
+        9_999,
+        10_000,
+        10_001,
+        123_456_789,
+        1_000_000_000,
+        57_502_679_529,
+        u64::MAX / 10_000,
+        u64::MAX,
+    ];
+    const FEES: [u16; 8] = [0, 1, 50, 69, 100, 2_500, 9_999, 10_000];
+
+    #[test]
+    fn fee_plus_net_equals_gross() {
+        for amount in AMOUNTS {
+            for fee_bps in FEES {
+                let split = split_fee(amount, fee_bps).unwrap();
+                assert_eq!(split.fee + split.net, amount, "{amount} @ {fee_bps}bps");
+            }
+        }
+    }
+
+    #[test]
+    fn fee_rounds_up_in_favour_of_protocol() {
+        for amount in AMOUNTS {
+            for fee_bps in FEES {
+                let fee = calculate_fee(amount, fee_bps).unwrap() as u128;
+                let exact = amount as u128 * fee_bps as u128;
+                assert!(fee * BPS_DENOMINATOR as u128 >= exact);
+                assert!(fee * (BPS_DENOMINATOR as u128) < exact + BPS_DENOMINATOR as u128);
+            }
+        }
+        assert_eq!(calculate_fee(1, 1), Some(1));
+        assert_eq!(calculate_fee(1_000_000_000, 69), Some(6_900_000));
+    }
+
+    #[test]
+    fn fee_bounds() {
+        assert_eq!(calculate_fee(u64::MAX, 0), Some(0));
+        assert_eq!(calculate_fee(u64::MAX, 10_000), Some(u64::MAX));
+        assert_eq!(calculate_fee(1, 10_001), None);
+        assert_eq!(split_fee(1, 10_001), None);
+    }
+
+    #[test]
+    fn gross_for_net_is_the_smallest_covering_amount() {
+        for amount in AMOUNTS {
+            for fee_bps in FEES {
+                let Some(gross) = gross_for_net(amount, fee_bps) else {
+                    assert!(fee_bps == 10_000 || amount > u64::MAX / 2, "{amount} @ {fee_bps}bps");
+                    continue;
+                };
+                assert!(split_fee(gross, fee_bps).unwrap().net >= amount);
+                if gross > 0 {
+                    assert!(split_fee(gross - 1, fee_bps).unwrap().net < amount);
+                }
+            }
+        }
+        assert_eq!(gross_for_net(0, 10_000), Some(0));
+        assert_eq!(gross_for_net(1, 10_000), None);
+    }
+
+    #[test]
+    fn prorated_fee_never_exceeds_the_full_fee() {
+        assert_eq!(prorate_fee(6_900_000, 1_000_000_000, 1_000_000_000), Some(6_900_000));
+        assert_eq!(prorate_fee(6_900_000, 500_000_000, 1_000_000_000), Some(3_450_000));
+        assert_eq!(prorate_fee(69, 1, 1_000_000_000), Some(1));
+        assert_eq!(prorate_fee(69, 0, 1_000_000_000), Some(0));
+        assert_eq!(prorate_fee(0, 0, 0), Some(0));
+        assert_eq!(prorate_fee(69, 2, 1), None);
+    }
+
+    #[test]
+    fn fee_shares_round_down() {
+        assert_eq!(fee_share(10_000, 50, 150), Some(3_333));
+        assert_eq!(fee_share(10_000, 150, 150), Some(10_000));
+        assert_eq!(fee_share(1, 50, 150), Some(0));
+        assert_eq!(fee_share(u64::MAX, 10_000, 10_000), Some(u64::MAX));
+        assert_eq!(fee_share(10_000, 0, 0), Some(0));
+        assert_eq!(fee_share(10_000, 151, 150), None);
+    }
+
+    #[test]
+    fn legacy_percentage_conversion() {
+        assert_eq!(bps_from_percentage(0.0), Some(0));
+        assert_eq!(bps_from_percentage(0.69), Some(69));
+        assert_eq!(bps_from_percentage(0.07), Some(7));
+        assert_eq!(bps_from_percentage(100.0), Some(10_000));
+        assert_eq!(bps_from_percentage(-0.1), None);
+        assert_eq!(bps_from_percentage(100.1), None);
+        assert_eq!(bps_from_percentage(f64::NAN), None);
+    }
+}
@@ -0,0 +1,134 @@
+use crate::errors::*;
+use crate::{
+    constants::{CONFIG, PENDING_CONFIG_CHANGE},
+    events::{ConfigChangeCancelledEvent, ConfigChangeQueuedEvent, ConfigUpdatedEvent},
+    state::{config::*, config_change::*},
+};
+use anchor_lang::{prelude::*, system_program};
+
+//  fee, wallet and delay changes wait `config_change_delay` seconds in a `PendingConfigChange`
+//  so integrators can react before they apply.
+#[derive(Accounts)]
+pub struct QueueConfigChange<'info> {
+    #[account(mut)]
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ContractError::IncorrectAuthority
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PendingConfigChange::INIT_SPACE,
+        seeds = [PENDING_CONFIG_CHANGE.as_bytes()],
+        bump
+    )]
+    pending_change: Box<Account<'info, PendingConfigChange>>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> QueueConfigChange<'info> {
+    pub fn handler(&mut self, change: ConfigUpdate) -> Result<()> {
+        require!(change.is_timelocked(), ContractError::NotTimelocked);
+
+        //  reject changes that could never execute now, rather than once the delay has passed
+        let mut preview = (**self.global_config).clone();
+        preview.apply_update(change.clone())?;
+        preview.validate()?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let pending = PendingConfigChange::new(change, now, self.global_config.config_change_delay)
+            .ok_or(ContractError::ArithmeticError)?;
+
+        emit!(ConfigChangeQueuedEvent {
+            authority: self.authority.key(),
+            change: pending.change.clone(),
+            executable_at: pending.executable_at,
+        });
+
+        self.pending_change.set_inner(pending);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ExecuteConfigChange<'info> {
+    #[account(mut)]
+    authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ContractError::IncorrectAuthority
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [PENDING_CONFIG_CHANGE.as_bytes()],
+        bump
+    )]
+    pending_change: Box<Account<'info, PendingConfigChange>>,
+}
+
+impl<'info> ExecuteConfigChange<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            self.pending_change.is_executable(now),
+            ContractError::ConfigChangeNotReady
+        );
+
+        let change = self.pending_change.change.clone();
+        let old = self.global_config.apply_update(change.clone())?;
+        self.global_config.validate()?;
+
+        emit!(ConfigUpdatedEvent {
+            authority: self.authority.key(),
+            old,
+            new: change,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct CancelConfigChange<'info> {
+    #[account(mut)]
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ContractError::IncorrectAuthority
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [PENDING_CONFIG_CHANGE.as_bytes()],
+        bump
+    )]
+    pending_change: Box<Account<'info, PendingConfigChange>>,
+}
+
+impl<'info> CancelConfigChange<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        emit!(ConfigChangeCancelledEvent {
+            authority: self.authority.key(),
+            change: self.pending_change.change.clone(),
+        });
+
+        Ok(())
+    }
+}
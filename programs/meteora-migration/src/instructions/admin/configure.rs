@@ -9,7 +9,6 @@ use anchor_spl::{
     associated_token::AssociatedToken,
     token::{Mint, Token, TokenAccount},
 };
-use borsh::BorshDeserialize;
 
 #[derive(Accounts)]
 pub struct Configure<'info> {
@@ -54,55 +53,44 @@ pub struct Configure<'info> {
 }
 
 impl<'info> Configure<'info> {
-    pub fn handler(&mut self, new_config: Config, config_bump: u8) -> Result<()> {
+    pub fn handler(&mut self, mut new_config: Config, config_bump: u8) -> Result<()> {
+        new_config.version = CONFIG_VERSION;
+        new_config.pending_authority = Pubkey::default();
+        new_config.pending_migration_authority = Pubkey::default();
+        new_config.reserved = [0; CONFIG_RESERVED];
+
+        //  every later change goes through the `set_*` and `queue_config_change` instructions
+        //  and their events, older layouts through `migrate_config`
+        require!(
+            self.config.owner != &crate::ID,
+            ContractError::ConfigAlreadyInitialized
+        );
+        new_config.initialized = true;
+        //  only `pause` / `unpause` and their events halt actions
+        new_config.paused = 0;
+
+        new_config.validate()?;
+
         let serialized_config =
             [&Config::DISCRIMINATOR, new_config.try_to_vec()?.as_slice()].concat();
-        let serialized_config_len = serialized_config.len();
-        let config_cost = Rent::get()?.minimum_balance(serialized_config_len);
+        let config_space = 8 + Config::INIT_SPACE;
 
         //  init config pda
-        if self.config.owner != &crate::ID {
-            let cpi_context = CpiContext::new(
-                self.system_program.to_account_info(),
-                system_program::CreateAccount {
-                    from: self.payer.to_account_info(),
-                    to: self.config.to_account_info(),
-                },
-            );
-            system_program::create_account(
-                cpi_context.with_signer(&[&[CONFIG.as_bytes(), &[config_bump]]]),
-                config_cost,
-                serialized_config_len as u64,
-                &crate::ID,
-            )?;
-        } else {
-            let data = self.config.try_borrow_data()?;
-            if data.len() < 8 || &data[0..8] != Config::DISCRIMINATOR {
-                return err!(ContractError::IncorrectConfigAccount);
-            }
-            let config = Config::deserialize(&mut &data[8..])?;
-
-            if config.authority != self.payer.key() {
-                return err!(ContractError::IncorrectAuthority);
-            }
-        }
-
-        let lamport_delta = (config_cost as i64) - (self.config.lamports() as i64);
-        if lamport_delta > 0 {
-            system_program::transfer(
-                CpiContext::new(
-                    self.system_program.to_account_info(),
-                    system_program::Transfer {
-                        from: self.payer.to_account_info(),
-                        to: self.config.to_account_info(),
-                    },
-                ),
-                lamport_delta as u64,
-            )?;
-            self.config.realloc(serialized_config_len, false)?;
-        }
+        let cpi_context = CpiContext::new(
+            self.system_program.to_account_info(),
+            system_program::CreateAccount {
+                from: self.payer.to_account_info(),
+                to: self.config.to_account_info(),
+            },
+        );
+        system_program::create_account(
+            cpi_context.with_signer(&[&[CONFIG.as_bytes(), &[config_bump]]]),
+            Rent::get()?.minimum_balance(config_space),
+            config_space as u64,
+            &crate::ID,
+        )?;
 
-        (self.config.try_borrow_mut_data()?[..serialized_config_len])
+        (self.config.try_borrow_mut_data()?[..serialized_config.len()])
             .copy_from_slice(serialized_config.as_slice());
 
         //  initialize global vault if needed
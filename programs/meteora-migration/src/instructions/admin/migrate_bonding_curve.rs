@@ -0,0 +1,83 @@
+use crate::errors::*;
+use crate::{
+    constants::{BONDING_CURVE, CONFIG, CONFIG_TOKEN_DECIMALS},
+    state::{bondingcurve::*, config::*},
+    utils::{grow_account, scale_token_amount},
+};
+use anchor_lang::{prelude::*, system_program, Discriminator};
+use anchor_spl::token_interface::Mint;
+use borsh::BorshDeserialize;
+
+//  upgrades a curve written in an older layout to `BONDING_CURVE_VERSION`, growing the
+//  account when needed. run once per curve by the authority right after upgrading the program.
+#[derive(Accounts)]
+pub struct MigrateBondingCurve<'info> {
+    #[account(mut)]
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ContractError::IncorrectAuthority
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: still in an older layout, deserialized inside the instruction
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE.as_bytes(), &token_mint.key().to_bytes()],
+        bump,
+        owner = crate::ID,
+    )]
+    bonding_curve: AccountInfo<'info>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateBondingCurve<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        let token_mint = self.token_mint.key();
+        let version = {
+            let data = self.bonding_curve.try_borrow_data()?;
+            if data.len() < 8 || data[0..8] != BondingCurve::DISCRIMINATOR {
+                return err!(ContractError::IncorrectBondingCurveAccount);
+            }
+            BondingCurve::stored_version(&data, &token_mint)
+                .ok_or(ContractError::IncorrectBondingCurveAccount)?
+        };
+        require!(
+            version < BONDING_CURVE_VERSION,
+            ContractError::AccountAlreadyMigrated
+        );
+
+        //  fields the oldest curves were allocated without start zeroed
+        grow_account(
+            &self.bonding_curve,
+            &self.authority,
+            &self.system_program,
+            8 + BondingCurve::INIT_SPACE,
+        )?;
+
+        //  the first curves were all launched with the config's real token reserves
+        let launch_real_token_reserves = scale_token_amount(
+            self.global_config.initial_real_token_reserves_config,
+            CONFIG_TOKEN_DECIMALS,
+            self.token_mint.decimals,
+        )
+        .ok_or(ContractError::ArithmeticError)?;
+
+        //  every layout so far is the unversioned one, future versions add their own arms
+        let bonding_curve = {
+            let data = self.bonding_curve.try_borrow_data()?;
+            LegacyBondingCurve::deserialize(&mut &data[8..])?
+                .into_bonding_curve(self.token_mint.decimals, launch_real_token_reserves)
+        };
+
+        bonding_curve.try_serialize(&mut &mut self.bonding_curve.try_borrow_mut_data()?[..])?;
+
+        Ok(())
+    }
+}
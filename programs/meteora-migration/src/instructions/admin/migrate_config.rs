@@ -0,0 +1,64 @@
+use crate::errors::*;
+use crate::{constants::CONFIG, state::config::*, utils::grow_account};
+use anchor_lang::{prelude::*, system_program, Discriminator};
+use borsh::BorshDeserialize;
+
+//  upgrades a config written in an older layout to `CONFIG_VERSION`, growing the account
+//  when needed. run once by the authority right after upgrading the program.
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    #[account(mut)]
+    authority: Signer<'info>,
+
+    /// CHECK: still in an older layout, deserialized inside the instruction
+    #[account(
+        mut,
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    config: AccountInfo<'info>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateConfig<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        let legacy_config = {
+            let data = self.config.try_borrow_data()?;
+            if self.config.owner != &crate::ID
+                || data.len() < 8
+                || data[0..8] != Config::DISCRIMINATOR
+            {
+                return err!(ContractError::IncorrectConfigAccount);
+            }
+            require!(
+                Config::current(&data).is_none(),
+                ContractError::AccountAlreadyMigrated
+            );
+            //  the only older layout is the unversioned one, future versions add their own arms
+            LegacyConfig::deserialize(&mut &data[8..])?
+        };
+
+        if legacy_config.authority != self.authority.key() {
+            return err!(ContractError::IncorrectAuthority);
+        }
+
+        //  fails on anything else, whose fee bytes are not valid percentages
+        let new_config = legacy_config.into_config()?;
+
+        let serialized_config =
+            [&Config::DISCRIMINATOR, new_config.try_to_vec()?.as_slice()].concat();
+
+        grow_account(
+            &self.config,
+            &self.authority,
+            &self.system_program,
+            8 + Config::INIT_SPACE,
+        )?;
+        (self.config.try_borrow_mut_data()?[..serialized_config.len()])
+            .copy_from_slice(serialized_config.as_slice());
+
+        Ok(())
+    }
+}
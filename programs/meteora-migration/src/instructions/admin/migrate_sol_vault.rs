@@ -0,0 +1,90 @@
+use crate::errors::*;
+use crate::{
+    constants::{BONDING_CURVE, CONFIG, GLOBAL, SOL_VAULT},
+    state::{bondingcurve::*, config::*},
+    utils::{sol_transfer_from_user, sol_transfer_with_signer},
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token_interface::Mint;
+
+//  moves the SOL of a curve launched before per-curve vaults out of the shared global vault.
+//  the curve has to be upgraded by `migrate_bonding_curve` first.
+#[derive(Accounts)]
+pub struct MigrateSolVault<'info> {
+    #[account(mut)]
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ContractError::IncorrectAuthority
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    /// CHECK: global vault pda which stores SOL
+    #[account(
+        mut,
+        seeds = [GLOBAL.as_bytes()],
+        bump,
+    )]
+    pub global_vault: AccountInfo<'info>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE.as_bytes(), &token_mint.key().to_bytes()],
+        bump,
+        constraint = bonding_curve.is_current(&token_mint.key()) @ContractError::AccountNeedsMigration
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    /// CHECK: sol vault pda of this bonding curve
+    #[account(
+        mut,
+        seeds = [SOL_VAULT.as_bytes(), &token_mint.key().to_bytes()],
+        bump,
+    )]
+    pub sol_vault: AccountInfo<'info>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateSolVault<'info> {
+    pub fn handler(&mut self, global_vault_bump: u8) -> Result<()> {
+        let bonding_curve = &mut self.bonding_curve;
+
+        require!(
+            !bonding_curve.has_sol_vault,
+            ContractError::SolVaultAlreadyInitialized
+        );
+
+        //  rent for the new vault is paid by the authority, not by other curves
+        let sol_vault_rent = Rent::get()?.minimum_balance(0);
+        if self.sol_vault.lamports() < sol_vault_rent {
+            sol_transfer_from_user(
+                &self.authority,
+                self.sol_vault.clone(),
+                &self.system_program,
+                sol_vault_rent - self.sol_vault.lamports(),
+            )?;
+        }
+
+        if bonding_curve.real_sol_reserves > 0 {
+            let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL.as_bytes(), &[global_vault_bump]]];
+
+            sol_transfer_with_signer(
+                self.global_vault.clone(),
+                self.sol_vault.clone(),
+                &self.system_program,
+                signer_seeds,
+                bonding_curve.real_sol_reserves,
+            )?;
+        }
+
+        bonding_curve.has_sol_vault = true;
+
+        Ok(())
+    }
+}
@@ -1 +1,9 @@
-pub mod configure;
\ No newline at end of file
+pub mod config_change;
+pub mod configure;
+pub mod migrate_bonding_curve;
+pub mod migrate_config;
+pub mod migrate_sol_vault;
+pub mod pause;
+pub mod transfer_authority;
+pub mod update_config;
+pub mod whitelist;
\ No newline at end of file
@@ -0,0 +1,94 @@
+use crate::errors::*;
+use crate::{
+    constants::{BONDING_CURVE, CONFIG},
+    events::{CurveFrozenEvent, PausedEvent, UnpausedEvent},
+    state::{bondingcurve::*, config::*},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+//  halts or resumes the `PAUSE_*` actions across every curve
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ContractError::IncorrectAuthority
+    )]
+    global_config: Box<Account<'info, Config>>,
+}
+
+impl<'info> SetPaused<'info> {
+    pub fn pause(&mut self, flags: u8) -> Result<()> {
+        require!(
+            flags != 0 && flags & !ALL_PAUSE_FLAGS == 0,
+            ContractError::InvalidPauseFlags
+        );
+        self.global_config.paused |= flags;
+
+        emit!(PausedEvent {
+            authority: self.authority.key(),
+            flags,
+            paused: self.global_config.paused,
+        });
+
+        Ok(())
+    }
+
+    pub fn unpause(&mut self, flags: u8) -> Result<()> {
+        require!(
+            flags != 0 && flags & !ALL_PAUSE_FLAGS == 0,
+            ContractError::InvalidPauseFlags
+        );
+        self.global_config.paused &= !flags;
+
+        emit!(UnpausedEvent {
+            authority: self.authority.key(),
+            flags,
+            paused: self.global_config.paused,
+        });
+
+        Ok(())
+    }
+}
+
+//  freezes or unfreezes trading and migration of a single curve
+#[derive(Accounts)]
+pub struct FreezeCurve<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ContractError::IncorrectAuthority
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE.as_bytes(), &token_mint.key().to_bytes()],
+        bump,
+        constraint = bonding_curve.is_current(&token_mint.key()) @ContractError::AccountNeedsMigration
+    )]
+    bonding_curve: Account<'info, BondingCurve>,
+}
+
+impl<'info> FreezeCurve<'info> {
+    pub fn handler(&mut self, frozen: bool) -> Result<()> {
+        self.bonding_curve.is_frozen = frozen;
+
+        emit!(CurveFrozenEvent {
+            authority: self.authority.key(),
+            mint: self.token_mint.key(),
+            bonding_curve: self.bonding_curve.key(),
+            frozen,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+use crate::errors::*;
+use crate::{
+    constants::CONFIG,
+    events::{AuthorityAcceptedEvent, AuthorityProposedEvent, AuthorityTransferCancelledEvent},
+    state::config::*,
+};
+use anchor_lang::prelude::*;
+
+//  proposing or cancelling a transfer of either role is done by the current `authority`,
+//  the role only moves once the proposed key signs `accept_*` itself.
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ContractError::IncorrectAuthority
+    )]
+    global_config: Box<Account<'info, Config>>,
+}
+
+impl<'info> ProposeAuthority<'info> {
+    pub fn propose(&mut self, role: AuthorityRole, new_authority: Pubkey) -> Result<()> {
+        self.global_config.propose_authority(role, new_authority)?;
+
+        emit!(AuthorityProposedEvent {
+            role,
+            authority: self.global_config.authority_of(role),
+            pending_authority: new_authority,
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel(&mut self, role: AuthorityRole) -> Result<()> {
+        let cancelled_authority = self.global_config.cancel_authority_transfer(role)?;
+
+        emit!(AuthorityTransferCancelledEvent {
+            role,
+            authority: self.global_config.authority_of(role),
+            cancelled_authority,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+}
+
+impl<'info> AcceptAuthority<'info> {
+    pub fn handler(&mut self, role: AuthorityRole) -> Result<()> {
+        let new_authority = self.new_authority.key();
+        let previous_authority = self.global_config.accept_authority(role, new_authority)?;
+
+        emit!(AuthorityAcceptedEvent {
+            role,
+            previous_authority,
+            authority: new_authority,
+        });
+
+        Ok(())
+    }
+}
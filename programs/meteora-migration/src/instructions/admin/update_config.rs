@@ -0,0 +1,35 @@
+use crate::errors::*;
+use crate::{constants::CONFIG, events::ConfigUpdatedEvent, state::config::*};
+use anchor_lang::prelude::*;
+
+//  changes one group of settings without re-sending the whole `Config`. fees, wallets and
+//  the delay itself go through `queue_config_change` instead.
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ContractError::IncorrectAuthority
+    )]
+    global_config: Box<Account<'info, Config>>,
+}
+
+impl<'info> UpdateConfig<'info> {
+    pub fn handler(&mut self, update: ConfigUpdate) -> Result<()> {
+        require!(!update.is_timelocked(), ContractError::TimelockRequired);
+
+        let old = self.global_config.apply_update(update.clone())?;
+        self.global_config.validate()?;
+
+        emit!(ConfigUpdatedEvent {
+            authority: self.authority.key(),
+            old,
+            new: update,
+        });
+
+        Ok(())
+    }
+}
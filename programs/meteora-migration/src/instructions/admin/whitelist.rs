@@ -0,0 +1,168 @@
+use crate::errors::*;
+use crate::{
+    constants::{CONFIG, MAX_WHITELIST_BATCH, WHITELIST},
+    events::WhitelistUpdatedEvent,
+    state::{config::*, whitelist::*},
+    utils::create_pda_account,
+};
+use anchor_lang::{prelude::*, system_program, Discriminator};
+
+#[derive(Accounts)]
+#[instruction(creator: Pubkey)]
+pub struct AddToWhitelist<'info> {
+    #[account(mut)]
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ContractError::IncorrectAuthority
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + WhitelistEntry::INIT_SPACE,
+        seeds = [WHITELIST.as_bytes(), creator.as_ref()],
+        bump
+    )]
+    whitelist_entry: Account<'info, WhitelistEntry>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> AddToWhitelist<'info> {
+    pub fn handler(&mut self, creator: Pubkey) -> Result<()> {
+        self.whitelist_entry.set_inner(WhitelistEntry {
+            creator,
+            added_at: Clock::get()?.unix_timestamp,
+        });
+
+        emit!(WhitelistUpdatedEvent {
+            authority: self.authority.key(),
+            creator,
+            whitelisted: true,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(creator: Pubkey)]
+pub struct RemoveFromWhitelist<'info> {
+    #[account(mut)]
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ContractError::IncorrectAuthority
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [WHITELIST.as_bytes(), creator.as_ref()],
+        bump
+    )]
+    whitelist_entry: Account<'info, WhitelistEntry>,
+}
+
+impl<'info> RemoveFromWhitelist<'info> {
+    pub fn handler(&mut self, creator: Pubkey) -> Result<()> {
+        emit!(WhitelistUpdatedEvent {
+            authority: self.authority.key(),
+            creator,
+            whitelisted: false,
+        });
+
+        Ok(())
+    }
+}
+
+//  adds up to `MAX_WHITELIST_BATCH` creators at once. the entry pdas are passed as
+//  remaining accounts in the same order as `creators`, entries that already exist are skipped.
+#[derive(Accounts)]
+pub struct BatchAddToWhitelist<'info> {
+    #[account(mut)]
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ContractError::IncorrectAuthority
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> BatchAddToWhitelist<'info> {
+    pub fn handler(
+        &mut self,
+        creators: Vec<Pubkey>,
+        whitelist_entries: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(
+            creators.len() <= MAX_WHITELIST_BATCH,
+            ContractError::WhitelistBatchTooLarge
+        );
+        require!(
+            creators.len() == whitelist_entries.len(),
+            ContractError::ValueInvalid
+        );
+
+        let space = 8 + WhitelistEntry::INIT_SPACE;
+        let now = Clock::get()?.unix_timestamp;
+
+        for (creator, whitelist_entry) in creators.into_iter().zip(whitelist_entries) {
+            let (address, bump) = Pubkey::find_program_address(
+                &[WHITELIST.as_bytes(), creator.as_ref()],
+                &crate::ID,
+            );
+            require_keys_eq!(whitelist_entry.key(), address, ContractError::ValueInvalid);
+
+            if whitelist_entry.owner == &crate::ID {
+                continue;
+            }
+            //  anything else at the entry's address has to still be a plain system account
+            require!(whitelist_entry.is_writable, ErrorCode::AccountNotMutable);
+            require_keys_eq!(
+                *whitelist_entry.owner,
+                system_program::ID,
+                ErrorCode::AccountNotSystemOwned
+            );
+
+            create_pda_account(
+                whitelist_entry,
+                &self.authority,
+                &self.system_program,
+                space,
+                &crate::ID,
+                &[&[WHITELIST.as_bytes(), creator.as_ref(), &[bump]]],
+            )?;
+
+            let entry = WhitelistEntry {
+                creator,
+                added_at: now,
+            };
+            let serialized_entry =
+                [&WhitelistEntry::DISCRIMINATOR, entry.try_to_vec()?.as_slice()].concat();
+            (whitelist_entry.try_borrow_mut_data()?[..serialized_entry.len()])
+                .copy_from_slice(serialized_entry.as_slice());
+
+            emit!(WhitelistUpdatedEvent {
+                authority: self.authority.key(),
+                creator,
+                whitelisted: true,
+            });
+        }
+
+        Ok(())
+    }
+}
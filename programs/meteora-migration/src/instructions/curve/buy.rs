@@ -0,0 +1,173 @@
+use crate::{
+    constants::{BONDING_CURVE, CONFIG, GLOBAL, PRESALE, REFERRER, SOL_VAULT},
+    errors::*,
+    state::{
+        bondingcurve::*,
+        config::*,
+        launch::PresaleAllowance,
+        referral::{Referral, ReferrerStats},
+    },
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token_interface::{Mint, TokenInterface},
+};
+
+#[derive(Accounts)]
+pub struct Buy<'info> {
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    /// CHECK: should be same with the address in the global_config
+    #[account(
+        mut,
+        constraint = global_config.team_wallet == team_wallet.key() @ContractError::IncorrectAuthority
+    )]
+    pub team_wallet: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE.as_bytes(), &token_mint.key().to_bytes()],
+        bump,
+        constraint = bonding_curve.is_current(&token_mint.key()) @ContractError::AccountNeedsMigration
+    )]
+    bonding_curve: Account<'info, BondingCurve>,
+
+    /// CHECK: global vault pda which stores SOL
+    #[account(
+        mut,
+        seeds = [GLOBAL.as_bytes()],
+        bump,
+    )]
+    pub global_vault: AccountInfo<'info>,
+
+    /// CHECK: sol vault pda of this bonding curve
+    #[account(
+        mut,
+        seeds = [SOL_VAULT.as_bytes(), &token_mint.key().to_bytes()],
+        bump,
+    )]
+    pub sol_vault: AccountInfo<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: ata of global vault
+    #[account(
+        mut,
+        seeds = [
+            global_vault.key().as_ref(),
+            token_program.key().as_ref(),
+            token_mint.key().as_ref(),
+        ],
+        bump,
+        seeds::program = associated_token::ID
+    )]
+    global_ata: AccountInfo<'info>,
+
+    /// CHECK: ata of user, created in instruction if needed
+    #[account(
+        mut,
+        seeds = [
+            user.key().as_ref(),
+            token_program.key().as_ref(),
+            token_mint.key().as_ref(),
+        ],
+        bump,
+        seeds::program = associated_token::ID
+    )]
+    user_ata: AccountInfo<'info>,
+
+    //  required for buys during the curve's presale
+    #[account(
+        mut,
+        seeds = [PRESALE.as_bytes(), token_mint.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    presale_allowance: Option<Account<'info, PresaleAllowance>>,
+
+    /// CHECK: receives the referral fee, checked against `referrer_stats`
+    #[account(mut)]
+    referrer: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [REFERRER.as_bytes(), referrer_stats.referrer.as_ref()],
+        bump = referrer_stats.bump,
+    )]
+    referrer_stats: Option<Account<'info, ReferrerStats>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(address = associated_token::ID)]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> Buy<'info> {
+    pub fn handler(
+        &mut self,
+        swap_amount: SwapAmount,
+        global_vault_bump: u8,
+        sol_vault_bump: u8,
+    ) -> Result<SwapQuote> {
+        //  create user wallet ata, if it doesn't exist
+        if self.user_ata.data_is_empty() {
+            associated_token::create(CpiContext::new(
+                self.associated_token_program.to_account_info(),
+                associated_token::Create {
+                    payer: self.user.to_account_info(),
+                    associated_token: self.user_ata.to_account_info(),
+                    authority: self.user.to_account_info(),
+
+                    mint: self.token_mint.to_account_info(),
+                    system_program: self.system_program.to_account_info(),
+                    token_program: self.token_program.to_account_info(),
+                },
+            ))?;
+        }
+
+        let global_vault_signer: &[&[&[u8]]] = &[&[GLOBAL.as_bytes(), &[global_vault_bump]]];
+
+        let token_mint_key = self.token_mint.key();
+        let sol_vault_signer: &[&[&[u8]]] = &[&[
+            SOL_VAULT.as_bytes(),
+            token_mint_key.as_ref(),
+            &[sol_vault_bump],
+        ]];
+
+        let referral = Referral::new(
+            self.referrer.as_deref(),
+            self.referrer_stats.as_mut(),
+            &self.user.key(),
+        )?;
+
+        self.bonding_curve.swap(
+            &self.global_config,
+            &self.token_mint,
+            &mut self.global_ata,
+            &mut self.user_ata,
+            &mut self.global_vault,
+            &mut self.sol_vault,
+            &mut self.team_wallet,
+            Direction::Buy,
+            swap_amount,
+            self.presale_allowance.as_mut(),
+            referral,
+            &self.user,
+            global_vault_signer,
+            sol_vault_signer,
+            &self.token_program,
+            &self.system_program,
+        )
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{
+    constants::{CONFIG, TEAM_VESTING},
+    errors::*,
+    events::VestedClaimedEvent,
+    state::{config::*, vesting::*},
+    utils::token_transfer_with_signer,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+//  sends the unlocked part of a launch's team share to the current team wallet
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(mut)]
+    team_wallet: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        constraint = global_config.team_wallet == team_wallet.key() @ContractError::IncorrectTeamWallet
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [TEAM_VESTING.as_bytes(), token_mint.key().as_ref()],
+        bump = team_vesting.bump,
+    )]
+    team_vesting: Box<Account<'info, TeamVesting>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = team_vesting,
+        associated_token::token_program = token_program,
+    )]
+    team_vesting_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = team_wallet,
+        associated_token::mint = token_mint,
+        associated_token::authority = team_wallet,
+        associated_token::token_program = token_program,
+    )]
+    team_wallet_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+
+    token_program: Interface<'info, TokenInterface>,
+
+    #[account(address = associated_token::ID)]
+    associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> ClaimVested<'info> {
+    pub fn handler(&mut self) -> Result<u64> {
+        let amount = self
+            .team_vesting
+            .claimable_amount(Clock::get()?.unix_timestamp);
+        require!(amount > 0, ContractError::NothingToClaim);
+
+        self.team_vesting.claimed_amount += amount;
+
+        let token_mint_key = self.token_mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            TEAM_VESTING.as_bytes(),
+            token_mint_key.as_ref(),
+            &[self.team_vesting.bump],
+        ]];
+        token_transfer_with_signer(
+            self.team_vesting_token_account.to_account_info(),
+            self.team_vesting.to_account_info(),
+            self.team_wallet_token_account.to_account_info(),
+            self.token_mint.to_account_info(),
+            self.token_mint.decimals,
+            &self.token_program,
+            signer_seeds,
+            amount,
+        )?;
+
+        emit!(VestedClaimedEvent {
+            team_wallet: self.team_wallet.key(),
+            mint: token_mint_key,
+            amount,
+            claimed_amount: self.team_vesting.claimed_amount,
+            total_amount: self.team_vesting.total_amount,
+        });
+
+        Ok(amount)
+    }
+}
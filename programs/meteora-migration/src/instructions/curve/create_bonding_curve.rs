@@ -1,14 +1,26 @@
 use crate::{
-    constants::{BONDING_CURVE, CONFIG, GLOBAL, METADATA},
+    constants::{
+        BONDING_CURVE, CONFIG, CONFIG_TOKEN_DECIMALS, GLOBAL, METADATA, SOL_VAULT, TEAM_VESTING,
+        WHITELIST,
+    },
     errors::*,
-    events::LaunchEvent,
-    state::{bondingcurve::*, config::*},
+    events::{CompleteEvent, LaunchEvent, SwapEvent},
+    state::{
+        bondingcurve::*,
+        config::*,
+        launch::{InitialBuy, LaunchSchedule},
+        pricing::{CurveKind, Rounding},
+        vesting::TeamVesting,
+        whitelist::WhitelistEntry,
+    },
+    mint::migrates_to_meteora,
+    utils::{scale_token_amount, sol_transfer_from_user},
 };
 use anchor_lang::{prelude::*, solana_program::sysvar::SysvarId, system_program};
 use anchor_spl::{
     associated_token::{self, AssociatedToken},
     metadata::{self, mpl_token_metadata::types::DataV2, Metadata},
-    token::{self, spl_token::instruction::AuthorityType, Mint, Token},
+    token_interface::{self, spl_token_2022::instruction::AuthorityType, Mint, TokenInterface},
 };
 
 #[derive(Accounts)]
@@ -32,23 +44,39 @@ pub struct CreateBondingCurve<'info> {
     #[account(mut)]
     creator: Signer<'info>,
 
+    //  only required while `global_config.whitelist_enabled` is set
+    #[account(
+        seeds = [WHITELIST.as_bytes(), creator.key().as_ref()],
+        bump,
+    )]
+    whitelist_entry: Option<Account<'info, WhitelistEntry>>,
+
     #[account(
         init,
         payer = creator,
         mint::decimals = decimals,
         mint::authority = global_vault.key(),
+        mint::token_program = token_program,
     )]
-    token: Box<Account<'info, Mint>>,
+    token: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         init,
         payer = creator,
-        space = 8 + std::mem::size_of::<BondingCurve>(),
+        space = 8 + BondingCurve::INIT_SPACE,
         seeds = [BONDING_CURVE.as_bytes(), &token.key().to_bytes()],
         bump
     )]
     bonding_curve: Box<Account<'info, BondingCurve>>,
 
+    /// CHECK: sol vault pda of this bonding curve
+    #[account(
+        mut,
+        seeds = [SOL_VAULT.as_bytes(), &token.key().to_bytes()],
+        bump,
+    )]
+    pub sol_vault: AccountInfo<'info>,
+
     /// CHECK: passed to token metadata program
     #[account(
         mut,
@@ -67,7 +95,7 @@ pub struct CreateBondingCurve<'info> {
         mut,
         seeds = [
             global_vault.key().as_ref(),
-            token::spl_token::ID.as_ref(),
+            token_program.key().as_ref(),
             token.key().as_ref(),
         ],
         bump,
@@ -75,14 +103,43 @@ pub struct CreateBondingCurve<'info> {
     )]
     global_token_account: UncheckedAccount<'info>,
 
+    //  escrow of the share of the supply not minted to the curve, only passed when there is one
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + TeamVesting::INIT_SPACE,
+        seeds = [TEAM_VESTING.as_bytes(), token.key().as_ref()],
+        bump
+    )]
+    team_vesting: Option<Box<Account<'info, TeamVesting>>>,
+
+    /// CHECK: ata of team_vesting, created in instruction by the associated token program
+    #[account(mut)]
+    team_vesting_token_account: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: ata of creator, only created for an initial buy
+    #[account(
+        mut,
+        seeds = [
+            creator.key().as_ref(),
+            token_program.key().as_ref(),
+            token.key().as_ref(),
+        ],
+        bump,
+        seeds::program = associated_token::ID
+    )]
+    creator_token_account: UncheckedAccount<'info>,
+
     #[account(address = system_program::ID)]
     system_program: Program<'info, System>,
 
     #[account(address = Rent::id())]
     rent: Sysvar<'info, Rent>,
 
-    #[account(address = token::ID)]
-    token_program: Program<'info, Token>,
+    #[account(
+        constraint = migrates_to_meteora(&token_program.key()) @ContractError::TokenProgramNotMigratable
+    )]
+    token_program: Interface<'info, TokenInterface>,
 
     #[account(address = associated_token::ID)]
     associated_token_program: Program<'info, AssociatedToken>,
@@ -100,6 +157,7 @@ pub struct CreateBondingCurve<'info> {
 }
 
 impl<'info> CreateBondingCurve<'info> {
+    #[allow(clippy::too_many_arguments)]
     pub fn handler(
         &mut self,
 
@@ -107,20 +165,29 @@ impl<'info> CreateBondingCurve<'info> {
         decimals: u8,
         token_supply: u64,
         reserve_lamport: u64,
+        curve_kind: CurveKind,
+        schedule: LaunchSchedule,
+        initial_buy: Option<InitialBuy>,
 
         // metadata
         name: String,
         symbol: String,
         uri: String,
         global_vault_bump: u8,
+        team_vesting_bump: Option<u8>,
     ) -> Result<()> {
         let global_config = &self.global_config;
         let creator = &self.creator;
         let token = &self.token;
         let global_token_account = &self.global_token_account;
-        let bonding_curve = &mut self.bonding_curve;
         let global_vault = &self.global_vault;
 
+        global_config.require_not_paused(PAUSE_CREATE)?;
+        require!(
+            !global_config.whitelist_enabled || self.whitelist_entry.is_some(),
+            ContractError::NotWhiteList
+        );
+
         //  check params
         let decimal_multiplier = 10u64.pow(decimals as u32);
         let fractional_tokens = token_supply % decimal_multiplier;
@@ -138,16 +205,94 @@ impl<'info> CreateBondingCurve<'info> {
 
         global_config.token_decimals_config.validate(&decimals)?;
 
+        require!(
+            global_config.allows_curve_kind(&curve_kind),
+            ContractError::CurveKindNotAllowed
+        );
+        curve_kind.validate()?;
+
+        let bonding_curve = &mut self.bonding_curve;
+
+        let schedule = schedule.resolve(Clock::get()?.unix_timestamp)?;
+
+        //  the curve gets `init_bonding_curve` percent of the supply, the rest vests to the team
+        let curve_params = global_config.curve_params();
+        let curve_tokens = curve_params.curve_tokens(token_supply as u128) as u64;
+        let team_tokens = curve_params.team_tokens(token_supply as u128) as u64;
+
         // create token launch pda
+        bonding_curve.version = BONDING_CURVE_VERSION;
         bonding_curve.token_mint = token.key();
         bonding_curve.creator = creator.key();
         bonding_curve.init_lamport = reserve_lamport;
 
-        bonding_curve.virtual_sol_reserves = global_config.initial_virtual_sol_reserves_config;
-        bonding_curve.virtual_token_reserves = global_config.initial_virtual_token_reserves_config;
+        //  config token amounts are rescaled into the mint's decimals
+        let initial_virtual_token_reserves = scale_token_amount(
+            global_config.initial_virtual_token_reserves_config,
+            CONFIG_TOKEN_DECIMALS,
+            decimals,
+        )
+        .ok_or(ContractError::ArithmeticError)?;
+        let initial_real_token_reserves = scale_token_amount(
+            global_config.initial_real_token_reserves_config,
+            CONFIG_TOKEN_DECIMALS,
+            decimals,
+        )
+        .ok_or(ContractError::ArithmeticError)?;
+
+        if curve_kind == CurveKind::ConstantProduct {
+            bonding_curve.virtual_sol_reserves = global_config.initial_virtual_sol_reserves_config;
+            bonding_curve.virtual_token_reserves = initial_virtual_token_reserves;
+        } else {
+            //  the other kinds don't price off virtual reserves, they just mirror the real ones
+            require!(
+                curve_kind
+                    .cost(0, initial_real_token_reserves, decimals, Rounding::Up)
+                    .is_some(),
+                ContractError::InvalidCurveParams
+            );
+            bonding_curve.virtual_sol_reserves = 0;
+            bonding_curve.virtual_token_reserves = initial_real_token_reserves;
+        }
+        //  whatever the curve's shape, selling out has to raise enough to seed the meteora pool
+        let completed_sol = curve_params
+            .completed_curve_sol(&curve_kind, initial_real_token_reserves, decimals)
+            .ok_or(ContractError::InvalidCurveParams)?;
+        global_config.require_raise_funds_pool(completed_sol)?;
+        //  the meteora pool is seeded from the curve's share as well, once it completes
+        let meteora_tokens = scale_token_amount(
+            global_config.initial_meteora_token_reserves,
+            CONFIG_TOKEN_DECIMALS,
+            decimals,
+        )
+        .ok_or(ContractError::ArithmeticError)?;
+        require!(
+            initial_real_token_reserves
+                .checked_add(meteora_tokens)
+                .is_some_and(|reserved| reserved <= curve_tokens),
+            ContractError::ReservesExceedSupply
+        );
+
         bonding_curve.real_sol_reserves = 0;
-        bonding_curve.real_token_reserves = global_config.initial_real_token_reserves_config;
+        bonding_curve.real_token_reserves = initial_real_token_reserves;
+        bonding_curve.initial_real_token_reserves = initial_real_token_reserves;
         bonding_curve.token_total_supply = token_supply;
+        bonding_curve.decimals = decimals;
+        bonding_curve.curve_kind = curve_kind;
+        bonding_curve.start_time = schedule.start_time;
+        bonding_curve.presale_end_time = schedule.presale_end_time;
+
+        //  fund the curve's sol vault up to rent exemption so trades of any size can land in it
+        let sol_vault_rent = Rent::get()?.minimum_balance(0);
+        if self.sol_vault.lamports() < sol_vault_rent {
+            sol_transfer_from_user(
+                creator,
+                self.sol_vault.clone(),
+                &self.system_program,
+                sol_vault_rent - self.sol_vault.lamports(),
+            )?;
+        }
+        bonding_curve.has_sol_vault = true;
 
         // create global token account
         associated_token::create(CpiContext::new(
@@ -164,18 +309,65 @@ impl<'info> CreateBondingCurve<'info> {
 
         let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL.as_bytes(), &[global_vault_bump]]];
 
-        // mint tokens to bonding curve & team
-        token::mint_to(
+        //  launches minting the whole supply to the curve have no team share, and no escrow for it
+        match (
+            team_tokens,
+            &mut self.team_vesting,
+            &self.team_vesting_token_account,
+            team_vesting_bump,
+        ) {
+            (0, None, None, _) => {}
+            (1.., Some(team_vesting), Some(team_vesting_token_account), Some(bump)) => {
+                associated_token::create(CpiContext::new(
+                    self.associated_token_program.to_account_info(),
+                    associated_token::Create {
+                        payer: creator.to_account_info(),
+                        associated_token: team_vesting_token_account.to_account_info(),
+                        authority: team_vesting.to_account_info(),
+                        mint: token.to_account_info(),
+                        token_program: self.token_program.to_account_info(),
+                        system_program: self.system_program.to_account_info(),
+                    },
+                ))?;
+
+                team_vesting.set_inner(TeamVesting {
+                    token_mint: token.key(),
+                    total_amount: team_tokens,
+                    claimed_amount: 0,
+                    start_time: schedule.start_time,
+                    cliff: curve_params.team_vesting_cliff,
+                    duration: curve_params.team_vesting_duration,
+                    bump,
+                });
+
+                token_interface::mint_to(
+                    CpiContext::new_with_signer(
+                        self.token_program.to_account_info(),
+                        token_interface::MintTo {
+                            mint: token.to_account_info(),
+                            to: team_vesting_token_account.to_account_info(),
+                            authority: global_vault.to_account_info(),
+                        },
+                        signer_seeds,
+                    ),
+                    team_tokens,
+                )?;
+            }
+            _ => return err!(ContractError::InvalidTeamVesting),
+        }
+
+        // mint tokens to bonding curve
+        token_interface::mint_to(
             CpiContext::new_with_signer(
                 self.token_program.to_account_info(),
-                token::MintTo {
+                token_interface::MintTo {
                     mint: token.to_account_info(),
                     to: global_token_account.to_account_info(),
                     authority: global_vault.to_account_info(),
                 },
                 signer_seeds,
             ),
-            token_supply,
+            curve_tokens,
         )?;
 
         // create metadata
@@ -208,10 +400,10 @@ impl<'info> CreateBondingCurve<'info> {
         )?;
 
         //  revoke mint authority
-        token::set_authority(
+        token_interface::set_authority(
             CpiContext::new_with_signer(
                 self.token_program.to_account_info(),
-                token::SetAuthority {
+                token_interface::SetAuthority {
                     current_authority: global_vault.to_account_info(),
                     account_or_mint: token.to_account_info(),
                 },
@@ -231,7 +423,84 @@ impl<'info> CreateBondingCurve<'info> {
             decimals,
             token_supply,
             reserve_lamport,
-            reserve_token: global_config.initial_real_token_reserves_config
+            reserve_token: initial_real_token_reserves,
+
+            start_time: schedule.start_time,
+            presale_end_time: schedule.presale_end_time,
+
+            curve_tokens,
+            team_tokens,
+        });
+
+        if let Some(initial_buy) = initial_buy {
+            self.initial_buy(initial_buy, global_vault_bump)?;
+        }
+
+        Ok(())
+    }
+
+    //  goes through the same fee and curve math and transfers as `buy`, skipping the launch
+    //  phase checks since the curve is not open to anyone else yet. the creator holds no
+    //  presale allowance, so the buy is not held to presale caps, only to `max_tokens_out`.
+    fn initial_buy(&mut self, initial_buy: InitialBuy, global_vault_bump: u8) -> Result<()> {
+        self.global_config.require_not_paused(PAUSE_BUY)?;
+
+        let bonding_curve: &mut Account<'info, BondingCurve> = &mut self.bonding_curve;
+        let quote = bonding_curve.apply_initial_buy(&self.global_config, initial_buy)?;
+
+        associated_token::create(CpiContext::new(
+            self.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: self.creator.to_account_info(),
+                associated_token: self.creator_token_account.to_account_info(),
+                authority: self.creator.to_account_info(),
+                mint: self.token.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+            },
+        ))?;
+
+        quote.transfer_buy(
+            self.token.to_account_info(),
+            self.bonding_curve.decimals,
+            self.global_token_account.to_account_info(),
+            self.creator_token_account.to_account_info(),
+            self.global_vault.clone(),
+            self.sol_vault.clone(),
+            self.team_wallet.clone(),
+            None,
+            &self.creator,
+            &[&[GLOBAL.as_bytes(), &[global_vault_bump]]],
+            &self.token_program,
+            &self.system_program,
+        )?;
+
+        if quote.completes_curve {
+            emit!(CompleteEvent {
+                user: self.creator.key(),
+                mint: self.token.key(),
+                bonding_curve: self.bonding_curve.key()
+            });
+        }
+
+        emit!(SwapEvent {
+            user: self.creator.key(),
+            mint: self.token.key(),
+            bonding_curve: self.bonding_curve.key(),
+
+            amount_in: quote.amount_paid(Direction::Buy),
+            direction: Direction::Buy,
+            exact_out: false,
+            //  the initial buy's only bound is its token cap, logged in place of a minimum
+            minimum_receive_amount: initial_buy.max_tokens_out,
+            amount_out: quote.legacy_amount_out(Direction::Buy),
+            fee_amount: quote.fee_amount,
+            creator_fee_amount: quote.creator_fee_amount,
+            referrer: None,
+            referral_fee_amount: 0,
+
+            virtual_sol_reserves: self.bonding_curve.virtual_sol_reserves,
+            virtual_token_reserves: self.bonding_curve.virtual_token_reserves
         });
 
         Ok(())
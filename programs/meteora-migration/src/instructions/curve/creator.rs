@@ -0,0 +1,145 @@
+use crate::{
+    constants::{BONDING_CURVE, SOL_VAULT},
+    errors::*,
+    events::{
+        CreatorFeesClaimedEvent, CreatorProposedEvent, CreatorTransferCancelledEvent,
+        CreatorTransferredEvent,
+    },
+    state::bondingcurve::*,
+    utils::sol_transfer_with_signer,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token_interface::Mint;
+
+//  pays the creator fees accrued in the curve's sol vault out to the curve's creator.
+//  they stay claimable after the curve migrates, the migration only moves the curve's SOL.
+#[derive(Accounts)]
+pub struct ClaimCreatorFees<'info> {
+    #[account(mut)]
+    creator: Signer<'info>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE.as_bytes(), &token_mint.key().to_bytes()],
+        bump,
+        constraint = bonding_curve.is_current(&token_mint.key()) @ContractError::AccountNeedsMigration,
+        constraint = bonding_curve.creator == creator.key() @ContractError::IncorrectAuthority
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    /// CHECK: sol vault pda of this bonding curve
+    #[account(
+        mut,
+        seeds = [SOL_VAULT.as_bytes(), &token_mint.key().to_bytes()],
+        bump,
+    )]
+    sol_vault: AccountInfo<'info>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> ClaimCreatorFees<'info> {
+    pub fn handler(&mut self, sol_vault_bump: u8) -> Result<u64> {
+        let amount = std::mem::take(&mut self.bonding_curve.creator_fees);
+        require!(amount > 0, ContractError::NoCreatorFees);
+
+        let token_mint_key = self.token_mint.key();
+        sol_transfer_with_signer(
+            self.sol_vault.clone(),
+            self.creator.to_account_info(),
+            &self.system_program,
+            &[&[
+                SOL_VAULT.as_bytes(),
+                token_mint_key.as_ref(),
+                &[sol_vault_bump],
+            ]],
+            amount,
+        )?;
+
+        emit!(CreatorFeesClaimedEvent {
+            creator: self.creator.key(),
+            mint: token_mint_key,
+            amount,
+        });
+
+        Ok(amount)
+    }
+}
+
+//  proposing or cancelling a transfer of the curve's creator role is done by its creator,
+//  the role, with its fees, presale allowlist and lp lock share, only moves once the
+//  proposed key signs `accept_creator` itself.
+#[derive(Accounts)]
+pub struct ProposeCreator<'info> {
+    creator: Signer<'info>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE.as_bytes(), &token_mint.key().to_bytes()],
+        bump,
+        constraint = bonding_curve.is_current(&token_mint.key()) @ContractError::AccountNeedsMigration,
+        constraint = bonding_curve.creator == creator.key() @ContractError::IncorrectAuthority
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+}
+
+impl<'info> ProposeCreator<'info> {
+    pub fn propose(&mut self, new_creator: Pubkey) -> Result<()> {
+        self.bonding_curve.propose_creator(new_creator)?;
+
+        emit!(CreatorProposedEvent {
+            mint: self.token_mint.key(),
+            creator: self.creator.key(),
+            pending_creator: new_creator,
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel(&mut self) -> Result<()> {
+        let cancelled_creator = self.bonding_curve.cancel_creator_transfer()?;
+
+        emit!(CreatorTransferCancelledEvent {
+            mint: self.token_mint.key(),
+            creator: self.creator.key(),
+            cancelled_creator,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct AcceptCreator<'info> {
+    new_creator: Signer<'info>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE.as_bytes(), &token_mint.key().to_bytes()],
+        bump,
+        constraint = bonding_curve.is_current(&token_mint.key()) @ContractError::AccountNeedsMigration
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+}
+
+impl<'info> AcceptCreator<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        let new_creator = self.new_creator.key();
+        let previous_creator = self.bonding_curve.accept_creator(new_creator)?;
+
+        emit!(CreatorTransferredEvent {
+            mint: self.token_mint.key(),
+            previous_creator,
+            creator: new_creator,
+        });
+
+        Ok(())
+    }
+}
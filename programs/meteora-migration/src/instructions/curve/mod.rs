@@ -1,3 +1,10 @@
+pub mod buy;
+pub mod claim_vested;
 pub mod create_bonding_curve;
+pub mod creator;
 pub use create_bonding_curve::*;
+pub mod presale;
+pub mod quote;
+pub mod referral;
+pub mod sell;
 pub mod swap;
\ No newline at end of file
@@ -0,0 +1,67 @@
+use crate::{
+    constants::{BONDING_CURVE, PRESALE},
+    errors::*,
+    events::PresaleAllowanceEvent,
+    state::{bondingcurve::*, launch::*},
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token_interface::Mint;
+
+//  the creator allowlists `wallet` for the presale, or changes its cap. lowering the cap
+//  below what the wallet already spent just stops its further presale buys.
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct SetPresaleAllowance<'info> {
+    #[account(mut)]
+    creator: Signer<'info>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [BONDING_CURVE.as_bytes(), &token_mint.key().to_bytes()],
+        bump,
+        constraint = bonding_curve.is_current(&token_mint.key()) @ContractError::AccountNeedsMigration,
+        constraint = bonding_curve.creator == creator.key() @ContractError::IncorrectAuthority
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + PresaleAllowance::INIT_SPACE,
+        seeds = [PRESALE.as_bytes(), token_mint.key().as_ref(), wallet.as_ref()],
+        bump
+    )]
+    presale_allowance: Box<Account<'info, PresaleAllowance>>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> SetPresaleAllowance<'info> {
+    pub fn handler(&mut self, wallet: Pubkey, cap: u64) -> Result<()> {
+        //  allowances only matter until the presale ends
+        let phase = self
+            .bonding_curve
+            .schedule()
+            .phase(Clock::get()?.unix_timestamp);
+        require!(
+            phase != LaunchPhase::Public,
+            ContractError::IncorrectLaunchPhase
+        );
+
+        self.presale_allowance.token_mint = self.token_mint.key();
+        self.presale_allowance.wallet = wallet;
+        self.presale_allowance.cap = cap;
+
+        emit!(PresaleAllowanceEvent {
+            creator: self.creator.key(),
+            mint: self.token_mint.key(),
+            wallet,
+            cap,
+            spent: self.presale_allowance.spent,
+        });
+
+        Ok(())
+    }
+}
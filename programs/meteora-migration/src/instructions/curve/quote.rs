@@ -0,0 +1,85 @@
+use crate::{
+    constants::{BONDING_CURVE, CONFIG, REFERRER},
+    errors::*,
+    state::{bondingcurve::*, config::*, referral::ReferrerStats},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+//  read-only pricing of a trade, meant for `simulateTransaction`. runs the same curve and fee
+//  math as `swap` against a copy of the bonding curve and returns the result as return data.
+#[derive(Accounts)]
+pub struct Quote<'info> {
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [BONDING_CURVE.as_bytes(), &token_mint.key().to_bytes()],
+        bump,
+        constraint = bonding_curve.is_current(&token_mint.key()) @ContractError::AccountNeedsMigration
+    )]
+    bonding_curve: Account<'info, BondingCurve>,
+
+    //  quotes the referral split of trades that would pass this referrer
+    #[account(
+        seeds = [REFERRER.as_bytes(), referrer_stats.referrer.as_ref()],
+        bump = referrer_stats.bump,
+    )]
+    referrer_stats: Option<Account<'info, ReferrerStats>>,
+}
+
+impl<'info> Quote<'info> {
+    pub fn quote_buy(&self, sol_amount: u64) -> Result<SwapQuote> {
+        let quote = self
+            .curve(Direction::Buy)?
+            .apply_buy_with_fee(&self.global_config, sol_amount)?;
+        self.refer(quote)
+    }
+
+    pub fn quote_sell(&self, token_amount: u64) -> Result<SwapQuote> {
+        let quote = self
+            .curve(Direction::Sell)?
+            .apply_sell_with_fee(&self.global_config, token_amount)?;
+        self.refer(quote)
+    }
+
+    //  `amount` is the tokens to receive for buys and the SOL to receive for sells
+    pub fn quote_exact_out(&self, amount: u64, direction: Direction) -> Result<SwapQuote> {
+        let mut bonding_curve = self.curve(direction)?;
+
+        let quote = match direction {
+            Direction::Buy => {
+                bonding_curve.apply_buy_exact_out_with_fee(&self.global_config, amount)?
+            }
+            Direction::Sell => {
+                bonding_curve.apply_sell_exact_out_with_fee(&self.global_config, amount)?
+            }
+        };
+        self.refer(quote)
+    }
+
+    //  trades are applied to a copy, the account itself is never written. presale caps are
+    //  left to `swap`, quotes don't know the buyer.
+    fn curve(&self, direction: Direction) -> Result<Account<'info, BondingCurve>> {
+        self.bonding_curve.require_tradable(
+            &self.global_config,
+            direction,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        Ok(self.bonding_curve.clone())
+    }
+
+    fn refer(&self, mut quote: SwapQuote) -> Result<SwapQuote> {
+        if self.referrer_stats.is_some() {
+            quote.refer(&self.global_config)?;
+        }
+
+        Ok(quote)
+    }
+}
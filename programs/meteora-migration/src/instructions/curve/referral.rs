@@ -0,0 +1,39 @@
+use crate::{constants::REFERRER, events::ReferrerRegisteredEvent, state::referral::ReferrerStats};
+use anchor_lang::{prelude::*, system_program};
+
+//  opens the stats account a referrer is identified by on buys and sells
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    #[account(mut)]
+    referrer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = referrer,
+        space = 8 + ReferrerStats::INIT_SPACE,
+        seeds = [REFERRER.as_bytes(), referrer.key().as_ref()],
+        bump
+    )]
+    referrer_stats: Box<Account<'info, ReferrerStats>>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> RegisterReferrer<'info> {
+    pub fn handler(&mut self, bump: u8) -> Result<()> {
+        self.referrer_stats.set_inner(ReferrerStats {
+            referrer: self.referrer.key(),
+            trade_count: 0,
+            volume: 0,
+            fees_earned: 0,
+            bump,
+        });
+
+        emit!(ReferrerRegisteredEvent {
+            referrer: self.referrer.key(),
+        });
+
+        Ok(())
+    }
+}
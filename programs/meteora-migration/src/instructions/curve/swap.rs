@@ -1,13 +1,12 @@
 use anchor_lang::{system_program, prelude::*};
 use anchor_spl::{
     associated_token::{self, AssociatedToken},
-    token::{self, Mint, Token},
+    token_interface::{Mint, TokenInterface},
 };
 use crate::{
-    constants::{BONDING_CURVE, CONFIG, GLOBAL}, 
+    constants::{BONDING_CURVE, CONFIG, GLOBAL, PRESALE, SOL_VAULT}, 
     errors::*, 
-    events::SwapEvent,
-    state::{bondingcurve::*,  config::*}
+    state::{bondingcurve::*,  config::*, launch::PresaleAllowance}
 };
 
 #[derive(Accounts)]
@@ -28,7 +27,8 @@ pub struct Swap<'info> {
     #[account(
         mut,
         seeds = [BONDING_CURVE.as_bytes(), &token_mint.key().to_bytes()], 
-        bump
+        bump,
+        constraint = bonding_curve.is_current(&token_mint.key()) @ContractError::AccountNeedsMigration
     )]
     bonding_curve: Account<'info, BondingCurve>,
 
@@ -40,14 +40,23 @@ pub struct Swap<'info> {
     )]
     pub global_vault: AccountInfo<'info>,
 
-    pub token_mint: Box<Account<'info, Mint>>,
+    /// CHECK: sol vault pda of this bonding curve
+    #[account(
+        mut,
+        seeds = [SOL_VAULT.as_bytes(), &token_mint.key().to_bytes()],
+        bump,
+    )]
+    pub sol_vault: AccountInfo<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
 
     /// CHECK: ata of global vault
     #[account(
         mut,
         seeds = [
             global_vault.key().as_ref(),
-            anchor_spl::token::spl_token::ID.as_ref(),
+            token_program.key().as_ref(),
             token_mint.key().as_ref(),
         ],
         bump,
@@ -60,7 +69,7 @@ pub struct Swap<'info> {
         mut,
         seeds = [
             user.key().as_ref(),
-            anchor_spl::token::spl_token::ID.as_ref(),
+            token_program.key().as_ref(),
             token_mint.key().as_ref(),
         ],
         bump,
@@ -68,30 +77,34 @@ pub struct Swap<'info> {
     )]
     user_ata: AccountInfo<'info>,
 
+    //  required for buys during the curve's presale
+    #[account(
+        mut,
+        seeds = [PRESALE.as_bytes(), token_mint.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    presale_allowance: Option<Account<'info, PresaleAllowance>>,
+
     #[account(mut)]
     pub user: Signer<'info>,
 
     #[account(address = system_program::ID)]
     pub system_program: Program<'info, System>,
 
-    #[account(address = token::ID)]
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 
     #[account(address = associated_token::ID)]
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
 impl<'info> Swap<'info> { 
-pub fn handler(&mut self, amount: u64, direction: u8, minimum_receive_amount: u64,global_vault_bump:u8) -> Result<u64> {
+//  kept for clients built before `buy` and `sell`, which should be preferred
+pub fn handler(&mut self, amount: u64, direction: u8, minimum_receive_amount: u64, global_vault_bump: u8, sol_vault_bump: u8) -> Result<u64> {
+    let direction = Direction::try_from(direction)?;
     let bonding_curve = &mut self.bonding_curve;
 
-    //  check curve is not completed
-    require!(
-        bonding_curve.is_completed == false,
-        ContractError::CurveAlreadyCompleted
-    );
-
-    let source = &mut self.global_vault.to_account_info();
+    let global_vault = &mut self.global_vault.to_account_info();
+    let sol_vault = &mut self.sol_vault.to_account_info();
 
     let token = &mut self.token_mint;
     let team_wallet = &mut self.team_wallet;
@@ -113,46 +126,40 @@ pub fn handler(&mut self, amount: u64, direction: u8, minimum_receive_amount: u6
         ))?;
     }
 
-    let signer_seeds: &[&[&[u8]]] = &[&[
+    let global_vault_signer: &[&[&[u8]]] = &[&[
         GLOBAL.as_bytes(),
         &[global_vault_bump],
     ]];
 
-    let amount_out = bonding_curve.swap(
-        &*self.global_config,
+    let token_mint_key = token.key();
+    let sol_vault_signer: &[&[&[u8]]] = &[&[
+        SOL_VAULT.as_bytes(),
+        token_mint_key.as_ref(),
+        &[sol_vault_bump],
+    ]];
+
+    let quote = bonding_curve.swap(
+        &self.global_config,
         token.as_ref(),
         &mut self.global_ata,
         user_ata,
-        source,
+        global_vault,
+        sol_vault,
         team_wallet,
-        amount,
         direction,
-        minimum_receive_amount,
+        SwapAmount::ExactIn { amount, minimum_receive_amount },
+        self.presale_allowance.as_mut(),
+        None,
 
         &self.user,
-        signer_seeds,
+        global_vault_signer,
+        sol_vault_signer,
 
         &self.token_program,
         &self.system_program,
     )?;
 
-    emit!(
-        SwapEvent {
-            user: self.user.key(),
-            mint: self.token_mint.key(),
-            bonding_curve: bonding_curve.key(),
-
-            amount_in: amount,
-            direction,
-            minimum_receive_amount,
-            amount_out,
-
-            virtual_sol_reserves: bonding_curve.virtual_sol_reserves,
-            virtual_token_reserves: bonding_curve.virtual_token_reserves
-        }
-    );
-    
-    Ok(amount_out)
+    Ok(quote.legacy_amount_out(direction))
 }
 
 }
\ No newline at end of file
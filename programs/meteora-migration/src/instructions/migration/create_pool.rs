@@ -1,10 +1,15 @@
-use crate::constants::{METEORA_PROGRAM_KEY,CONFIG,  BONDING_CURVE, QUOTE_MINT, GLOBAL, TOKEN_VAULT_SEED};
+use crate::constants::{METEORA_PROGRAM_KEY,CONFIG,  BONDING_CURVE, QUOTE_MINT, GLOBAL, SOL_VAULT, TOKEN_VAULT_SEED, POOL_CREATION_RESERVE_LAMPORTS, CONFIG_TOKEN_DECIMALS};
+use crate::fees::calculate_fee;
+use crate::utils::scale_token_amount;
 use crate::state::{bondingcurve::*, meteora::get_pool_create_ix_data};
 use crate::{errors::ContractError, state::config::*};
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::program::invoke_signed;
 use anchor_lang::solana_program::{instruction::Instruction, system_instruction};
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::token::{self, Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenInterface};
+use crate::utils::token_transfer_with_signer;
+use crate::mint::migrates_to_meteora;
 use std::str::FromStr;
 
 #[derive(Accounts)]
@@ -22,12 +27,14 @@ pub struct InitializePoolWithConfig<'info> {
     )]
     pub team_wallet: AccountInfo<'info>,
 
-    pub token_mint: Box<Account<'info, Mint>>,
+    #[account(mint::token_program = token_mint_program)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
   
     #[account(
         mut,
         seeds = [BONDING_CURVE.as_bytes(), &token_mint.key().to_bytes()], 
-        bump
+        bump,
+        constraint = bonding_curve.is_current(&token_mint.key()) @ContractError::AccountNeedsMigration
     )]
     bonding_curve: Account<'info, BondingCurve>,
 
@@ -54,7 +61,7 @@ pub struct InitializePoolWithConfig<'info> {
     pub token_a_mint: UncheckedAccount<'info>,
 
     #[account(mut)]
-    pub token_b_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(mut)]
     /// CHECK: Vault accounts for token A
@@ -96,12 +103,20 @@ pub struct InitializePoolWithConfig<'info> {
     )]
     pub global_vault: AccountInfo<'info>,
 
+    /// CHECK: sol vault pda of this bonding curve
+    #[account(
+        mut,
+        seeds = [SOL_VAULT.as_bytes(), &token_mint.key().to_bytes()],
+        bump,
+    )]
+    pub sol_vault: AccountInfo<'info>,
+
     /// CHECK: ata of global vault
     #[account(
         mut,
         seeds = [
             global_vault.key().as_ref(),
-            anchor_spl::token::spl_token::ID.as_ref(),
+            token_mint_program.key().as_ref(),
             token_mint.key().as_ref(),
         ],
         bump,
@@ -146,6 +161,11 @@ pub struct InitializePoolWithConfig<'info> {
     pub vault_program: UncheckedAccount<'info>,
     /// CHECK: Token program account
     pub token_program: Program<'info, Token>,
+    /// CHECK: Token program of the launched mint, only ones meteora can hold
+    #[account(
+        constraint = migrates_to_meteora(&token_mint_program.key()) @ContractError::TokenProgramNotMigratable
+    )]
+    pub token_mint_program: Interface<'info, TokenInterface>,
     /// CHECK: Associated token program account
     pub associated_token_program: UncheckedAccount<'info>,
     /// CHECK: System program account
@@ -186,13 +206,30 @@ pub fn initialize_pool_with_config(ctx: Context<InitializePoolWithConfig>) -> Re
         ContractError::InvalidMeteoraProgram
     );
 
+    ctx.accounts.global_config.require_not_paused(PAUSE_MIGRATE)?;
+    require!(
+        !ctx.accounts.bonding_curve.is_frozen,
+        ContractError::CurveFrozen
+    );
+
+    require!(
+        ctx.accounts.bonding_curve.has_sol_vault,
+        ContractError::SolVaultNotInitialized
+    );
+
+    let migration_fee = calculate_fee(
+        ctx.accounts.bonding_curve.real_sol_reserves,
+        ctx.accounts.global_config.platform_migration_fee_bps,
+    )
+    .ok_or(ContractError::ArithmeticError)?;
+
     let check_sol_amount = ctx
         .accounts
         .bonding_curve
         .real_sol_reserves
-        .checked_sub(2_000_000_000)
+        .checked_sub(migration_fee)
         .ok_or(ContractError::ArithmeticError)?
-        .checked_sub(40_000_000)
+        .checked_sub(POOL_CREATION_RESERVE_LAMPORTS)
         .ok_or(ContractError::ArithmeticError)?;
 
     require!(
@@ -200,47 +237,57 @@ pub fn initialize_pool_with_config(ctx: Context<InitializePoolWithConfig>) -> Re
     );
 
     let token_a_amount = ctx.accounts.global_config.initial_meteora_sol_amount;
-    let token_b_amount = ctx.accounts.global_config.initial_meteora_token_reserves;
+    let token_b_amount = scale_token_amount(
+        ctx.accounts.global_config.initial_meteora_token_reserves,
+        CONFIG_TOKEN_DECIMALS,
+        ctx.accounts.bonding_curve.decimals,
+    )
+    .ok_or(ContractError::ArithmeticError)?;
+
+    let leftover_sol = ctx
+        .accounts
+        .bonding_curve
+        .migrate(token_a_amount, migration_fee)?;
 
     let signer_seeds: &[&[&[u8]]] = &[&[
         GLOBAL.as_bytes(),
         &[ctx.bumps.global_vault],
     ]];
 
-    // Transfer Mint B to payer token b - Bonding Curve is Signer
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.global_token_account.to_account_info(),
-        to: ctx.accounts.payer_token_b.to_account_info(),
-        authority: ctx.accounts.global_vault.to_account_info(),
-    };
+    let token_mint_key = ctx.accounts.token_mint.key();
+    let sol_vault_signer_seeds: &[&[&[u8]]] = &[&[
+        SOL_VAULT.as_bytes(),
+        token_mint_key.as_ref(),
+        &[ctx.bumps.sol_vault],
+    ]];
 
-    token::transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            cpi_accounts,
-            signer_seeds,
-        ),
+    // Transfer Mint B to payer token b - Bonding Curve is Signer
+    token_transfer_with_signer(
+        ctx.accounts.global_token_account.to_account_info(),
+        ctx.accounts.global_vault.to_account_info(),
+        ctx.accounts.payer_token_b.to_account_info(),
+        ctx.accounts.token_mint.to_account_info(),
+        ctx.accounts.token_mint.decimals,
+        &ctx.accounts.token_mint_program,
+        signer_seeds,
         token_b_amount,
     )?;
 
     // Transfer and wrap sol to payer token a - Sol Escrow is Signer
     let sol_ix = system_instruction::transfer(
-        &ctx.accounts.global_vault.to_account_info().key,
-        &ctx.accounts.payer_token_a.to_account_info().key,
+        ctx.accounts.sol_vault.key,
+        ctx.accounts.payer_token_a.key,
         token_a_amount,
     );
 
     invoke_signed(
         &sol_ix,
         &[
-            ctx.accounts
-                .global_vault
-                .to_account_info()
-                .clone(),
-            ctx.accounts.payer_token_a.to_account_info().clone(),
+            ctx.accounts.sol_vault.to_account_info(),
+            ctx.accounts.payer_token_a.to_account_info(),
             ctx.accounts.system_program.to_account_info(),
         ],
-        signer_seeds,
+        sol_vault_signer_seeds,
     )?;
 
     // Sync Native mint ATA
@@ -330,24 +377,23 @@ pub fn initialize_pool_with_config(ctx: Context<InitializePoolWithConfig>) -> Re
         signer_seeds, // Signer is the SOL Escrow
     )?;
 
-    // Fee transfer
+    // Fee transfer, the SOL the pool did not take is swept along with it
     let sol_ix = system_instruction::transfer(
-        &ctx.accounts.global_vault.to_account_info().key,
-        &ctx.accounts.team_wallet.to_account_info().key,
-        2_000_000_000,
+        ctx.accounts.sol_vault.key,
+        ctx.accounts.team_wallet.key,
+        migration_fee
+            .checked_add(leftover_sol)
+            .ok_or(ContractError::ArithmeticError)?,
     );
 
     invoke_signed(
         &sol_ix,
         &[
-            ctx.accounts
-                .global_vault
-                .to_account_info()
-                .clone(),
+            ctx.accounts.sol_vault.to_account_info(),
             ctx.accounts.team_wallet.clone(),
             ctx.accounts.system_program.to_account_info(),
         ],
-        signer_seeds,
+        sol_vault_signer_seeds,
     )?;
 
     Ok(())
@@ -5,7 +5,7 @@ use crate::state::meteora::{get_function_hash, get_lock_lp_ix_data};
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::{instruction::Instruction, program::invoke_signed};
 use anchor_spl::{associated_token, token::TokenAccount};
-use anchor_spl::token:: Mint;
+use anchor_spl::token_interface::Mint;
 use std::str::FromStr;
 
 #[derive(Accounts)]
@@ -19,11 +19,12 @@ pub struct LockPool<'info> {
     #[account(
         mut,
         seeds = [BONDING_CURVE.as_bytes(), &token_mint.key().to_bytes()], 
-        bump
+        bump,
+        constraint = bonding_curve.is_current(&token_mint.key()) @ContractError::AccountNeedsMigration
     )]
     bonding_curve: Account<'info, BondingCurve>,
 
-    pub token_mint: Box<Account<'info, Mint>>,
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
 
     /// CHECK: global vault pda which stores SOL
     #[account(
@@ -133,6 +134,12 @@ pub fn lock_pool(ctx: Context<LockPool>) -> Result<()> {
         ContractError::InvalidMeteoraProgram
     );
 
+    ctx.accounts.global_config.require_not_paused(PAUSE_MIGRATE)?;
+    require!(
+        !ctx.accounts.bonding_curve.is_frozen,
+        ContractError::CurveFrozen
+    );
+
     let signer_seeds: &[&[&[u8]]] = &[&[
         GLOBAL.as_bytes(),
         &[ctx.bumps.global_vault],
@@ -2,12 +2,23 @@ use anchor_lang::prelude::*;
 pub mod constants;
 pub mod errors;
 pub mod events;
+pub mod fees;
 pub mod instructions;
+pub mod mint;
 pub mod state;
 pub mod utils;
 
-use instructions::{configure::*, create_bonding_curve::*, create_pool::*, lock_pool::*, swap::*};
-use state::config::*;
+use instructions::{
+    buy::*, claim_vested::*, config_change::*, configure::*, create_bonding_curve::*, create_pool::*, creator::*, lock_pool::*,
+    migrate_bonding_curve::*, migrate_config::*, migrate_sol_vault::*, pause::*, presale::*, quote::*, referral::*, sell::*, swap::*, transfer_authority::*,
+    update_config::*, whitelist::*,
+};
+use state::{
+    bondingcurve::{Direction, SwapAmount, SwapQuote},
+    config::*,
+    launch::{InitialBuy, LaunchSchedule},
+    pricing::CurveKind,
+};
 
 declare_id!("9MHPjXpZXgJrB4NiJVFStE5qy7Nqp7yaYpaqNe5jNfMw");
 
@@ -19,6 +30,103 @@ pub mod pump_meteora {
         ctx.accounts.handler(new_config, ctx.bumps.config)
     }
 
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn migrate_bonding_curve(ctx: Context<MigrateBondingCurve>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn migrate_sol_vault(ctx: Context<MigrateSolVault>) -> Result<()> {
+        ctx.accounts.handler(ctx.bumps.global_vault)
+    }
+
+    //  fee, wallet and delay changes only
+    pub fn queue_config_change(ctx: Context<QueueConfigChange>, change: ConfigUpdate) -> Result<()> {
+        ctx.accounts.handler(change)
+    }
+
+    pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn set_curve_params(ctx: Context<UpdateConfig>, params: CurveParams) -> Result<()> {
+        ctx.accounts.handler(ConfigUpdate::CurveParams(params))
+    }
+
+    pub fn set_launch_limits(ctx: Context<UpdateConfig>, limits: LaunchLimits) -> Result<()> {
+        ctx.accounts.handler(ConfigUpdate::LaunchLimits(limits))
+    }
+
+    pub fn set_whitelist_enabled(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        ctx.accounts.handler(ConfigUpdate::WhitelistEnabled(enabled))
+    }
+
+    pub fn add_to_whitelist(ctx: Context<AddToWhitelist>, creator: Pubkey) -> Result<()> {
+        ctx.accounts.handler(creator)
+    }
+
+    pub fn remove_from_whitelist(ctx: Context<RemoveFromWhitelist>, creator: Pubkey) -> Result<()> {
+        ctx.accounts.handler(creator)
+    }
+
+    //  the whitelist entry pdas of `creators` follow as remaining accounts
+    pub fn batch_add_to_whitelist<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchAddToWhitelist<'info>>,
+        creators: Vec<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.handler(creators, ctx.remaining_accounts)
+    }
+
+    pub fn pause(ctx: Context<SetPaused>, flags: u8) -> Result<()> {
+        ctx.accounts.pause(flags)
+    }
+
+    pub fn unpause(ctx: Context<SetPaused>, flags: u8) -> Result<()> {
+        ctx.accounts.unpause(flags)
+    }
+
+    pub fn freeze_curve(ctx: Context<FreezeCurve>) -> Result<()> {
+        ctx.accounts.handler(true)
+    }
+
+    pub fn unfreeze_curve(ctx: Context<FreezeCurve>) -> Result<()> {
+        ctx.accounts.handler(false)
+    }
+
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        ctx.accounts.propose(AuthorityRole::Authority, new_authority)
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        ctx.accounts.handler(AuthorityRole::Authority)
+    }
+
+    pub fn cancel_authority_transfer(ctx: Context<ProposeAuthority>) -> Result<()> {
+        ctx.accounts.cancel(AuthorityRole::Authority)
+    }
+
+    pub fn propose_migration_authority(
+        ctx: Context<ProposeAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.propose(AuthorityRole::MigrationAuthority, new_authority)
+    }
+
+    pub fn accept_migration_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        ctx.accounts.handler(AuthorityRole::MigrationAuthority)
+    }
+
+    pub fn cancel_migration_authority_transfer(ctx: Context<ProposeAuthority>) -> Result<()> {
+        ctx.accounts.cancel(AuthorityRole::MigrationAuthority)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn create_bonding_curve(
         ctx: Context<CreateBondingCurve>,
 
@@ -26,6 +134,9 @@ pub mod pump_meteora {
         decimals: u8,
         token_supply: u64,
         virtual_lamport_reserves: u64,
+        curve_kind: CurveKind,
+        schedule: LaunchSchedule,
+        initial_buy: Option<InitialBuy>,
 
         //  metadata
         name: String,
@@ -36,13 +147,52 @@ pub mod pump_meteora {
             decimals,
             token_supply,
             virtual_lamport_reserves,
+            curve_kind,
+            schedule,
+            initial_buy,
             name,
             symbol,
             uri,
             ctx.bumps.global_vault,
+            ctx.bumps.team_vesting,
         )
     }
 
+    //  only before the curve's presale ends
+    pub fn set_presale_allowance(
+        ctx: Context<SetPresaleAllowance>,
+        wallet: Pubkey,
+        cap: u64,
+    ) -> Result<()> {
+        ctx.accounts.handler(wallet, cap)
+    }
+
+    //  returns the tokens claimed
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<u64> {
+        ctx.accounts.handler()
+    }
+
+    //  returns the lamports claimed
+    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<u64> {
+        ctx.accounts.handler(ctx.bumps.sol_vault)
+    }
+
+    pub fn propose_creator(ctx: Context<ProposeCreator>, new_creator: Pubkey) -> Result<()> {
+        ctx.accounts.propose(new_creator)
+    }
+
+    pub fn accept_creator(ctx: Context<AcceptCreator>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn cancel_creator_transfer(ctx: Context<ProposeCreator>) -> Result<()> {
+        ctx.accounts.cancel()
+    }
+
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        ctx.accounts.handler(ctx.bumps.referrer_stats)
+    }
+
     pub fn swap(
         ctx: Context<Swap>,
         amount: u64,
@@ -54,9 +204,82 @@ pub mod pump_meteora {
             direction,
             minimum_receive_amount,
             ctx.bumps.global_vault,
+            ctx.bumps.sol_vault,
         )
     }
 
+    //  returns the tokens received
+    pub fn buy(ctx: Context<Buy>, amount: u64, minimum_receive_amount: u64) -> Result<u64> {
+        let quote = ctx.accounts.handler(
+            SwapAmount::ExactIn {
+                amount,
+                minimum_receive_amount,
+            },
+            ctx.bumps.global_vault,
+            ctx.bumps.sol_vault,
+        )?;
+        Ok(quote.amount_received(Direction::Buy))
+    }
+
+    //  returns the SOL received after the fee
+    pub fn sell(ctx: Context<Sell>, amount: u64, minimum_receive_amount: u64) -> Result<u64> {
+        let quote = ctx.accounts.handler(
+            SwapAmount::ExactIn {
+                amount,
+                minimum_receive_amount,
+            },
+            ctx.bumps.global_vault,
+            ctx.bumps.sol_vault,
+        )?;
+        Ok(quote.amount_received(Direction::Sell))
+    }
+
+    //  buys exactly `amount` tokens, returns the SOL paid including the fee
+    pub fn buy_exact_out(ctx: Context<Buy>, amount: u64, maximum_pay_amount: u64) -> Result<u64> {
+        let quote = ctx.accounts.handler(
+            SwapAmount::ExactOut {
+                amount,
+                maximum_pay_amount,
+            },
+            ctx.bumps.global_vault,
+            ctx.bumps.sol_vault,
+        )?;
+        Ok(quote.amount_paid(Direction::Buy))
+    }
+
+    //  sells for at least `amount` SOL after the fee, returns the tokens paid
+    pub fn sell_exact_out(
+        ctx: Context<Sell>,
+        amount: u64,
+        maximum_pay_amount: u64,
+    ) -> Result<u64> {
+        let quote = ctx.accounts.handler(
+            SwapAmount::ExactOut {
+                amount,
+                maximum_pay_amount,
+            },
+            ctx.bumps.global_vault,
+            ctx.bumps.sol_vault,
+        )?;
+        Ok(quote.amount_paid(Direction::Sell))
+    }
+
+    pub fn quote_buy(ctx: Context<Quote>, sol_amount: u64) -> Result<SwapQuote> {
+        ctx.accounts.quote_buy(sol_amount)
+    }
+
+    pub fn quote_sell(ctx: Context<Quote>, token_amount: u64) -> Result<SwapQuote> {
+        ctx.accounts.quote_sell(token_amount)
+    }
+
+    pub fn quote_exact_out(
+        ctx: Context<Quote>,
+        amount: u64,
+        direction: Direction,
+    ) -> Result<SwapQuote> {
+        ctx.accounts.quote_exact_out(amount, direction)
+    }
+
     pub fn create_pool(ctx: Context<InitializePoolWithConfig>) -> Result<()> {
         instructions::initialize_pool_with_config(ctx)
     }
@@ -0,0 +1,22 @@
+//  token programs launched mints may be owned by
+
+use anchor_lang::prelude::*;
+
+//  meteora's dynamic amm and its vaults only hold spl token mints, `create_pool` can't migrate
+//  curves of any other mint. launches are held to the same programs so their SOL can't get stuck.
+pub fn migrates_to_meteora(token_program: &Pubkey) -> bool {
+    *token_program == anchor_spl::token::ID
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_spl::{token, token_2022};
+
+    #[test]
+    fn only_mints_meteora_can_hold_are_launched() {
+        assert!(migrates_to_meteora(&token::ID));
+        assert!(!migrates_to_meteora(&token_2022::ID));
+        assert!(!migrates_to_meteora(&Pubkey::new_unique()));
+    }
+}
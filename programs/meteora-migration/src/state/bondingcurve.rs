@@ -1,16 +1,25 @@
+use crate::constants::LAMPORT_DECIMALS;
 use crate::errors::*;
-use crate::events::CompleteEvent;
+use crate::events::{CompleteEvent, SwapEvent};
+use crate::fees::{gross_for_net, prorate_fee};
 use crate::state::config::*;
+use crate::state::launch::{InitialBuy, LaunchPhase, LaunchSchedule, PresaleAllowance};
+use crate::state::pricing::{CurveKind, Rounding};
+use crate::state::referral::Referral;
 use crate::utils::*;
 use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
-use anchor_spl::token::Mint;
-use anchor_spl::token::Token;
-use std::ops::Div;
-use std::ops::Mul;
-use std::ops::Sub;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+
+//  layout written by this program, bumped whenever a field is added to `BondingCurve`
+pub const BONDING_CURVE_VERSION: u8 = 1;
+pub const BONDING_CURVE_RESERVED: usize = 7;
 
 #[account]
+#[derive(InitSpace)]
 pub struct BondingCurve {
+    //  `migrate_bonding_curve` upgrades curves whose version is below `BONDING_CURVE_VERSION`
+    pub version: u8,
+
     pub token_mint: Pubkey,
     pub creator: Pubkey,
 
@@ -25,6 +34,209 @@ pub struct BondingCurve {
     pub real_token_reserves: u64,
 
     pub is_completed: bool,
+
+    //  SOL of this curve is held in its own `SOL_VAULT` pda. Curves launched before
+    //  per-curve vaults existed keep this false until `migrate_sol_vault` is run.
+    pub has_sol_vault: bool,
+
+    //  decimals of `token_mint`, used to keep the curve math at lamport precision
+    pub decimals: u8,
+
+    pub curve_kind: CurveKind,
+    //  real token reserves at launch, curves other than constant product price off the tokens sold
+    pub initial_real_token_reserves: u64,
+
+    //  set by the authority to halt trading and migration of this curve alone
+    pub is_frozen: bool,
+
+    //  see `LaunchSchedule`, both read as zero on curves launched before schedules existed
+    pub start_time: i64,
+    pub presale_end_time: i64,
+
+    //  creator fees held in the curve's sol vault until `claim_creator_fees`
+    pub creator_fees: u64,
+
+    //  set once `create_pool` moved the curve to meteora, so it can only migrate once
+    pub migrated: bool,
+
+    //  proposed by `propose_creator`, only takes over the role once it signs `accept_creator`
+    pub pending_creator: Pubkey,
+
+    //  room for new fields, so they can be added without growing the account
+    pub reserved: [u8; BONDING_CURVE_RESERVED],
+}
+
+impl BondingCurve {
+    //  layout version of the curve of `token_mint` stored in `data`. unversioned curves start
+    //  with the mint itself, versioned ones with the version byte.
+    pub fn stored_version(data: &[u8], token_mint: &Pubkey) -> Option<u8> {
+        let fields = data.get(8..)?;
+        if fields.get(..32)? == token_mint.as_ref() {
+            return Some(0);
+        }
+        fields.first().copied()
+    }
+
+    //  instructions only operate on curves that were upgraded to the current layout
+    pub fn is_current(&self, token_mint: &Pubkey) -> bool {
+        self.version == BONDING_CURVE_VERSION && self.token_mint == *token_mint
+    }
+
+    //  checks every trade and its quote go through, returns the launch phase a trade at `now` is in
+    pub fn require_tradable(
+        &self,
+        global_config: &Config,
+        direction: Direction,
+        now: i64,
+    ) -> Result<LaunchPhase> {
+        require!(!self.is_completed, ContractError::CurveAlreadyCompleted);
+
+        //  legacy curves must move their SOL out of the global vault first
+        require!(self.has_sol_vault, ContractError::SolVaultNotInitialized);
+
+        global_config.require_not_paused(match direction {
+            Direction::Buy => PAUSE_BUY,
+            Direction::Sell => PAUSE_SELL,
+        })?;
+        require!(!self.is_frozen, ContractError::CurveFrozen);
+
+        let phase = self.schedule().phase(now);
+        require!(
+            phase != LaunchPhase::Scheduled,
+            ContractError::IncorrectLaunchPhase
+        );
+
+        Ok(phase)
+    }
+
+    pub fn schedule(&self) -> LaunchSchedule {
+        LaunchSchedule {
+            start_time: self.start_time,
+            presale_end_time: self.presale_end_time,
+        }
+    }
+
+    //  books the creator's part of a trade's fee, returns it
+    fn accrue_creator_fee(&mut self, global_config: &Config, fee: u64, fee_bps: u16) -> Result<u64> {
+        let creator_fee = global_config
+            .creator_fee(fee, fee_bps)
+            .ok_or(ContractError::ArithmeticError)?;
+        self.creator_fees = self
+            .creator_fees
+            .checked_add(creator_fee)
+            .ok_or(ContractError::ArithmeticError)?;
+
+        Ok(creator_fee)
+    }
+
+    //  books the move of a completed curve to meteora, returns the SOL left in its vault once
+    //  `pool_sol` and `migration_fee` are paid out of the raise. creator fees stay claimable.
+    pub fn migrate(&mut self, pool_sol: u64, migration_fee: u64) -> Result<u64> {
+        require!(self.is_completed, ContractError::NotCompleted);
+        require!(!self.migrated, ContractError::CurveAlreadyMigrated);
+
+        let leftover = self
+            .real_sol_reserves
+            .checked_sub(pool_sol)
+            .and_then(|sol| sol.checked_sub(migration_fee))
+            .ok_or(ContractError::ArithmeticError)?;
+
+        //  the leftover is swept out with the fee, the raise is fully spent
+        self.real_sol_reserves = 0;
+        self.migrated = true;
+
+        Ok(leftover)
+    }
+
+    pub fn propose_creator(&mut self, new_creator: Pubkey) -> Result<()> {
+        require!(
+            new_creator != Pubkey::default(),
+            ContractError::InvalidCreator
+        );
+
+        self.pending_creator = new_creator;
+        Ok(())
+    }
+
+    //  hands the creator role to the pending key, returns the key it was taken from
+    pub fn accept_creator(&mut self, signer: Pubkey) -> Result<Pubkey> {
+        require!(
+            self.pending_creator != Pubkey::default(),
+            ContractError::NoPendingCreator
+        );
+        require!(self.pending_creator == signer, ContractError::IncorrectAuthority);
+
+        self.pending_creator = Pubkey::default();
+        Ok(std::mem::replace(&mut self.creator, signer))
+    }
+
+    //  drops the pending proposal, returns the key that was proposed
+    pub fn cancel_creator_transfer(&mut self) -> Result<Pubkey> {
+        let pending = std::mem::take(&mut self.pending_creator);
+        require!(pending != Pubkey::default(), ContractError::NoPendingCreator);
+
+        Ok(pending)
+    }
+
+    pub fn tokens_sold(&self) -> Option<u64> {
+        self.initial_real_token_reserves
+            .checked_sub(self.real_token_reserves)
+    }
+}
+
+//  unversioned layout, only read by `migrate_bonding_curve`. the first curves were allocated
+//  before `has_sol_vault` and are read with the missing fields zeroed.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+pub struct LegacyBondingCurve {
+    pub token_mint: Pubkey,
+    pub creator: Pubkey,
+    pub init_lamport: u64,
+    pub token_total_supply: u64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub is_completed: bool,
+    pub has_sol_vault: bool,
+    pub decimals: u8,
+    pub curve_kind: CurveKind,
+    pub initial_real_token_reserves: u64,
+    pub is_frozen: bool,
+}
+
+impl LegacyBondingCurve {
+    //  `launch_real_token_reserves` stands in for the launch reserves the first curves never stored
+    pub fn into_bonding_curve(self, decimals: u8, launch_real_token_reserves: u64) -> BondingCurve {
+        let initial_real_token_reserves = match self.initial_real_token_reserves {
+            0 => launch_real_token_reserves.max(self.real_token_reserves),
+            initial => initial,
+        };
+
+        BondingCurve {
+            version: BONDING_CURVE_VERSION,
+            token_mint: self.token_mint,
+            creator: self.creator,
+            init_lamport: self.init_lamport,
+            token_total_supply: self.token_total_supply,
+            virtual_sol_reserves: self.virtual_sol_reserves,
+            virtual_token_reserves: self.virtual_token_reserves,
+            real_sol_reserves: self.real_sol_reserves,
+            real_token_reserves: self.real_token_reserves,
+            is_completed: self.is_completed,
+            has_sol_vault: self.has_sol_vault,
+            //  the first curves never stored the mint's decimals the curve math relies on
+            decimals,
+            curve_kind: self.curve_kind,
+            initial_real_token_reserves,
+            is_frozen: self.is_frozen,
+            start_time: 0,
+            presale_end_time: 0,
+            creator_fees: 0,
+            migrated: false,
+            pending_creator: Pubkey::default(),
+            reserved: [0; BONDING_CURVE_RESERVED],
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -33,39 +245,206 @@ pub struct SellResult {
     pub sol_amount: u64,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Buy,
+    Sell,
+}
+
+//  raw direction of the legacy `swap` instruction, anything but 0 or 1 is rejected
+impl TryFrom<u8> for Direction {
+    type Error = Error;
+
+    fn try_from(direction: u8) -> Result<Self> {
+        match direction {
+            0 => Ok(Self::Buy),
+            1 => Ok(Self::Sell),
+            _ => err!(ContractError::InvalidDirection),
+        }
+    }
+}
+
+//  exact-in trades fix what the user pays, exact-out trades fix what the user receives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapAmount {
+    ExactIn {
+        amount: u64,
+        minimum_receive_amount: u64,
+    },
+    ExactOut {
+        amount: u64,
+        maximum_pay_amount: u64,
+    },
+}
+
 #[derive(Debug, Clone)]
 pub struct BuyResult {
     pub token_amount: u64,
     pub sol_amount: u64,
 }
 
+//  everything a trade moves once fees are applied, returned as is by the quote instructions
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SwapQuote {
+    pub token_amount: u64,
+    pub sol_amount: u64, //  SOL entering or leaving the curve, fee excluded
+    pub fee_amount: u64,
+    pub creator_fee_amount: u64, //  part of `fee_amount` kept in the sol vault for the creator
+    pub referral_fee_amount: u64, //  part of `fee_amount` paid to the trade's referrer
+    pub completes_curve: bool,
+}
+
+impl SwapQuote {
+    //  SOL including the fee for buys, tokens for sells
+    pub fn amount_paid(&self, direction: Direction) -> u64 {
+        match direction {
+            Direction::Buy => self.sol_amount + self.fee_amount,
+            Direction::Sell => self.token_amount,
+        }
+    }
+
+    //  tokens for buys, SOL after the fee for sells
+    pub fn amount_received(&self, direction: Direction) -> u64 {
+        match direction {
+            Direction::Buy => self.token_amount,
+            Direction::Sell => self.sol_amount - self.fee_amount,
+        }
+    }
+
+    //  what the platform keeps of `fee_amount`, before any referral
+    pub fn platform_fee_amount(&self) -> u64 {
+        self.fee_amount - self.creator_fee_amount
+    }
+
+    //  what the team wallet receives of `fee_amount`
+    pub fn team_fee_amount(&self) -> u64 {
+        self.platform_fee_amount() - self.referral_fee_amount
+    }
+
+    //  books the referrer's slice of the platform fee, for trades that have a referrer
+    pub fn refer(&mut self, global_config: &Config) -> Result<()> {
+        self.referral_fee_amount = global_config
+            .referral_fee(self.platform_fee_amount())
+            .ok_or(ContractError::ArithmeticError)?;
+
+        Ok(())
+    }
+
+    //  what `swap` has always returned: SOL taken by the curve for buys, tokens for sells
+    pub fn legacy_amount_out(&self, direction: Direction) -> u64 {
+        match direction {
+            Direction::Buy => self.sol_amount,
+            Direction::Sell => self.token_amount,
+        }
+    }
+
+    //  moves the tokens and SOL of a buy, shared by `swap` and the creator's initial buy
+    #[allow(clippy::too_many_arguments)]
+    pub fn transfer_buy<'info>(
+        &self,
+        token_mint: AccountInfo<'info>,
+        decimals: u8,
+        global_ata: AccountInfo<'info>,
+        user_ata: AccountInfo<'info>,
+        global_vault: AccountInfo<'info>,
+        sol_vault: AccountInfo<'info>,
+        team_wallet: AccountInfo<'info>,
+        referrer: Option<AccountInfo<'info>>,
+
+        user: &Signer<'info>,
+        global_vault_signer: &[&[&[u8]]],
+
+        token_program: &Interface<'info, TokenInterface>,
+        system_program: &Program<'info, System>,
+    ) -> Result<()> {
+        token_transfer_with_signer(
+            global_ata,
+            global_vault,
+            user_ata,
+            token_mint,
+            decimals,
+            token_program,
+            global_vault_signer,
+            self.token_amount,
+        )?;
+
+        //  the creator's part of the fee is held in the sol vault with the curve's SOL
+        sol_transfer_from_user(
+            user,
+            sol_vault,
+            system_program,
+            self.sol_amount + self.creator_fee_amount,
+        )?;
+
+        //  transfer fee to team wallet
+        sol_transfer_from_user(user, team_wallet, system_program, self.team_fee_amount())?;
+
+        if let Some(referrer) = referrer {
+            sol_transfer_from_user(user, referrer, system_program, self.referral_fee_amount)?;
+        }
+
+        Ok(())
+    }
+}
+
 pub trait BondingCurveAccount<'info> {
+    #[allow(clippy::too_many_arguments)]
     fn swap(
         &mut self,
         global_config: &Account<'info, Config>,
-        token_mint: &Account<'info, Mint>,
+        token_mint: &InterfaceAccount<'info, Mint>,
         global_ata: &mut AccountInfo<'info>,
         user_ata: &mut AccountInfo<'info>,
-        source: &mut AccountInfo<'info>,
+        global_vault: &mut AccountInfo<'info>,
+        sol_vault: &mut AccountInfo<'info>,
         team_wallet: &mut AccountInfo<'info>,
-        amount: u64,
-        direction: u8,
-        minimum_receive_amount: u64,
+        direction: Direction,
+        swap_amount: SwapAmount,
+        presale_allowance: Option<&mut Account<'info, PresaleAllowance>>,
+        referral: Option<Referral<'_, 'info>>,
 
         user: &Signer<'info>,
-        signer: &[&[&[u8]]],
+        global_vault_signer: &[&[&[u8]]],
+        sol_vault_signer: &[&[&[u8]]],
 
-        token_program: &Program<'info, Token>,
+        token_program: &Interface<'info, TokenInterface>,
         system_program: &Program<'info, System>,
-    ) -> Result<u64>;
+    ) -> Result<SwapQuote>;
+
+    fn apply_buy_with_fee(&mut self, global_config: &Config, sol_amount: u64) -> Result<SwapQuote>;
+
+    fn apply_sell_with_fee(&mut self, global_config: &Config, token_amount: u64)
+        -> Result<SwapQuote>;
+
+    fn apply_buy_exact_out_with_fee(
+        &mut self,
+        global_config: &Config,
+        token_amount: u64,
+    ) -> Result<SwapQuote>;
+
+    fn apply_sell_exact_out_with_fee(
+        &mut self,
+        global_config: &Config,
+        sol_amount: u64,
+    ) -> Result<SwapQuote>;
+
+    fn apply_initial_buy(
+        &mut self,
+        global_config: &Config,
+        initial_buy: InitialBuy,
+    ) -> Result<SwapQuote>;
 
     fn apply_sell(&mut self, token_amount: u64) -> Option<SellResult>;
 
-    fn apply_buy(&mut self, sol_amount: u64) -> Option<BuyResult>;
+    fn apply_buy(&mut self, sol_amount: u64, sol_target: Option<u64>) -> Option<BuyResult>;
 
     fn get_sol_for_sell_tokens(&self, token_amount: u64) -> Option<u64>;
 
     fn get_tokens_for_buy_sol(&self, sol_amount: u64) -> Option<u64>;
+
+    fn get_sol_for_buy_tokens(&self, token_amount: u64) -> Option<u64>;
+
+    fn get_tokens_for_sell_sol(&self, sol_amount: u64) -> Option<u64>;
 }
 
 impl<'info> BondingCurveAccount<'info> for Account<'info, BondingCurve> {
@@ -73,81 +452,134 @@ impl<'info> BondingCurveAccount<'info> for Account<'info, BondingCurve> {
         &mut self,
         global_config: &Account<'info, Config>,
 
-        token_mint: &Account<'info, Mint>,
+        token_mint: &InterfaceAccount<'info, Mint>,
         global_ata: &mut AccountInfo<'info>,
         user_ata: &mut AccountInfo<'info>,
 
-        source: &mut AccountInfo<'info>,
+        global_vault: &mut AccountInfo<'info>,
+        sol_vault: &mut AccountInfo<'info>,
         team_wallet: &mut AccountInfo<'info>,
 
-        amount: u64,
-        direction: u8,
-        minimum_receive_amount: u64,
+        direction: Direction,
+        swap_amount: SwapAmount,
+        presale_allowance: Option<&mut Account<'info, PresaleAllowance>>,
+        referral: Option<Referral<'_, 'info>>,
 
         user: &Signer<'info>,
-        signer: &[&[&[u8]]],
+        global_vault_signer: &[&[&[u8]]],
+        sol_vault_signer: &[&[&[u8]]],
 
-        token_program: &Program<'info, Token>,
+        token_program: &Interface<'info, TokenInterface>,
         system_program: &Program<'info, System>,
-    ) -> Result<u64> {
-        if amount <= 0 {
-            return err!(ContractError::InvalidAmount);
-        }
-
-        let amount_out;
-
-        if direction == 1 {
-            //Sell tokens
-            let sell_result = self.apply_sell(amount).ok_or(ContractError::SellFailed)?;
+    ) -> Result<SwapQuote> {
+        let phase =
+            self.require_tradable(global_config, direction, Clock::get()?.unix_timestamp)?;
+
+        let quote = if direction == Direction::Sell {
+            let mut sell_result = match swap_amount {
+                SwapAmount::ExactIn {
+                    amount,
+                    minimum_receive_amount,
+                } => {
+                    let sell_result = self.apply_sell_with_fee(global_config, amount)?;
+                    require!(
+                        sell_result.sol_amount - sell_result.fee_amount >= minimum_receive_amount,
+                        ContractError::ReturnAmountTooSmall
+                    );
+                    sell_result
+                }
+                SwapAmount::ExactOut {
+                    amount,
+                    maximum_pay_amount,
+                } => {
+                    let sell_result = self.apply_sell_exact_out_with_fee(global_config, amount)?;
+                    require!(
+                        sell_result.token_amount <= maximum_pay_amount,
+                        ContractError::PayAmountTooLarge
+                    );
+                    sell_result
+                }
+            };
+            let net_amount = sell_result.sol_amount - sell_result.fee_amount;
+            if referral.is_some() {
+                sell_result.refer(global_config)?;
+            }
 
             token_transfer_user(
                 user_ata.clone(),
-                &user,
+                user,
                 global_ata.clone(),
-                &token_program,
+                token_mint.to_account_info(),
+                token_mint.decimals,
+                token_program,
                 sell_result.token_amount,
             )?;
 
-            let adjusted_amount_in_float = convert_to_float(sell_result.sol_amount, 9)
-                .div(100_f64)
-                .mul(100_f64.sub(global_config.platform_sell_fee));
-
-            let adjusted_amount = convert_from_float(adjusted_amount_in_float, 9);
-
             sol_transfer_with_signer(
-                source.clone(),
+                sol_vault.clone(),
                 user.to_account_info(),
-                &system_program,
-                signer,
-                adjusted_amount,
+                system_program,
+                sol_vault_signer,
+                net_amount,
             )?;
 
-            //  transfer fee to team wallet
-            let fee_amount = sell_result.sol_amount - adjusted_amount;
-
+            //  transfer fee to team wallet, the creator's part stays in the sol vault
             sol_transfer_with_signer(
-                source.clone(),
+                sol_vault.clone(),
                 team_wallet.clone(),
-                &system_program,
-                signer,
-                fee_amount,
+                system_program,
+                sol_vault_signer,
+                sell_result.team_fee_amount(),
             )?;
 
-            amount_out = sell_result.token_amount;
-        } else
-        //buy tokens
-        {
-            let adjusted_amount_in_float = convert_to_float(amount, 9)
-                .div(100_f64)
-                .mul(100_f64.sub(global_config.platform_sell_fee));
+            if let Some(referral) = &referral {
+                sol_transfer_with_signer(
+                    sol_vault.clone(),
+                    referral.referrer.clone(),
+                    system_program,
+                    sol_vault_signer,
+                    sell_result.referral_fee_amount,
+                )?;
+            }
 
-            let adjusted_amount = convert_from_float(adjusted_amount_in_float, 9);
+            sell_result
+        } else {
+            let mut buy_result = match swap_amount {
+                SwapAmount::ExactIn {
+                    amount,
+                    minimum_receive_amount,
+                } => {
+                    let buy_result = self.apply_buy_with_fee(global_config, amount)?;
+                    //  checked after `apply_buy` so a clamped last buy is covered too
+                    require!(
+                        buy_result.token_amount >= minimum_receive_amount,
+                        ContractError::ReturnAmountTooSmall
+                    );
+                    buy_result
+                }
+                SwapAmount::ExactOut {
+                    amount,
+                    maximum_pay_amount,
+                } => {
+                    let buy_result = self.apply_buy_exact_out_with_fee(global_config, amount)?;
+                    require!(
+                        buy_result.sol_amount + buy_result.fee_amount <= maximum_pay_amount,
+                        ContractError::PayAmountTooLarge
+                    );
+                    buy_result
+                }
+            };
+            if referral.is_some() {
+                buy_result.refer(global_config)?;
+            }
 
-            let buy_result = self
-                .apply_buy(adjusted_amount)
-                .ok_or(ContractError::BuyFailed)?;
+            if phase == LaunchPhase::Presale {
+                presale_allowance
+                    .ok_or(ContractError::IncorrectLaunchPhase)?
+                    .record_buy(buy_result.amount_paid(Direction::Buy))?;
+            }
 
-            if self.is_completed == true {
+            if self.is_completed {
                 emit!(CompleteEvent {
                     user: user.key(),
                     mint: token_mint.key(),
@@ -155,30 +587,218 @@ impl<'info> BondingCurveAccount<'info> for Account<'info, BondingCurve> {
                 });
             }
 
-            token_transfer_with_signer(
+            buy_result.transfer_buy(
+                token_mint.to_account_info(),
+                token_mint.decimals,
                 global_ata.clone(),
-                source.clone(),
                 user_ata.clone(),
-                &token_program,
-                signer,
-                buy_result.token_amount,
+                global_vault.clone(),
+                sol_vault.clone(),
+                team_wallet.clone(),
+                referral.as_ref().map(|referral| referral.referrer.clone()),
+                user,
+                global_vault_signer,
+                token_program,
+                system_program,
             )?;
+            buy_result
+        };
+
+        let referrer = match referral {
+            Some(referral) => {
+                referral
+                    .stats
+                    .record_trade(quote.sol_amount, quote.referral_fee_amount)?;
+                Some(referral.referrer.key())
+            }
+            None => None,
+        };
+
+        //  exact-out trades log what was paid and the amount that was asked for
+        let (amount_in, minimum_receive_amount, exact_out) = match swap_amount {
+            SwapAmount::ExactIn {
+                amount,
+                minimum_receive_amount,
+            } => (amount, minimum_receive_amount, false),
+            SwapAmount::ExactOut { amount, .. } => (quote.amount_paid(direction), amount, true),
+        };
+
+        emit!(SwapEvent {
+            user: user.key(),
+            mint: token_mint.key(),
+            bonding_curve: self.key(),
+
+            amount_in,
+            direction,
+            exact_out,
+            minimum_receive_amount,
+            amount_out: quote.legacy_amount_out(direction),
+            fee_amount: quote.fee_amount,
+            creator_fee_amount: quote.creator_fee_amount,
+            referrer,
+            referral_fee_amount: quote.referral_fee_amount,
+
+            virtual_sol_reserves: self.virtual_sol_reserves,
+            virtual_token_reserves: self.virtual_token_reserves
+        });
+
+        Ok(quote)
+    }
 
-            sol_transfer_from_user(
-                &user,
-                source.clone(),
-                &system_program,
-                buy_result.sol_amount,
-            )?;
+    fn apply_buy_with_fee(&mut self, global_config: &Config, sol_amount: u64) -> Result<SwapQuote> {
+        if sol_amount == 0 {
+            return err!(ContractError::InvalidAmount);
+        }
+
+        let fee_split = global_config
+            .buy_fee(sol_amount)
+            .ok_or(ContractError::ArithmeticError)?;
+
+        let buy_result = self
+            .apply_buy(fee_split.net, global_config.sol_target())
+            .ok_or(ContractError::BuyFailed)?;
+
+        //  a clamped last buy only pays the fee on the share of SOL the curve took
+        let fee_amount = if buy_result.sol_amount < fee_split.net {
+            prorate_fee(fee_split.fee, buy_result.sol_amount, fee_split.net)
+                .ok_or(ContractError::ArithmeticError)?
+        } else {
+            fee_split.fee
+        };
+        let creator_fee_amount =
+            self.accrue_creator_fee(global_config, fee_amount, global_config.buy_fee_bps())?;
+
+        Ok(SwapQuote {
+            token_amount: buy_result.token_amount,
+            sol_amount: buy_result.sol_amount,
+            fee_amount,
+            creator_fee_amount,
+            referral_fee_amount: 0,
+            completes_curve: self.is_completed,
+        })
+    }
 
-            //  transfer fee to team wallet
-            let fee_amount = amount - adjusted_amount;
+    fn apply_sell_with_fee(
+        &mut self,
+        global_config: &Config,
+        token_amount: u64,
+    ) -> Result<SwapQuote> {
+        if token_amount == 0 {
+            return err!(ContractError::InvalidAmount);
+        }
+
+        let sell_result = self
+            .apply_sell(token_amount)
+            .ok_or(ContractError::SellFailed)?;
+
+        let fee_split = global_config
+            .sell_fee(sell_result.sol_amount)
+            .ok_or(ContractError::ArithmeticError)?;
+        let creator_fee_amount =
+            self.accrue_creator_fee(global_config, fee_split.fee, global_config.sell_fee_bps())?;
+
+        Ok(SwapQuote {
+            token_amount: sell_result.token_amount,
+            sol_amount: sell_result.sol_amount,
+            fee_amount: fee_split.fee,
+            creator_fee_amount,
+            referral_fee_amount: 0,
+            completes_curve: false,
+        })
+    }
+
+    fn apply_buy_exact_out_with_fee(
+        &mut self,
+        global_config: &Config,
+        token_amount: u64,
+    ) -> Result<SwapQuote> {
+        if token_amount == 0 {
+            return err!(ContractError::InvalidAmount);
+        }
+        require!(
+            token_amount <= self.real_token_reserves,
+            ContractError::InsufficientTokens
+        );
+
+        let sol_amount = self
+            .get_sol_for_buy_tokens(token_amount)
+            .ok_or(ContractError::BuyFailed)?;
+
+        let buy_result = self
+            .apply_buy(sol_amount, global_config.sol_target())
+            .ok_or(ContractError::BuyFailed)?;
+
+        //  the curve hit its sol target before handing out every requested token
+        require!(
+            buy_result.token_amount >= token_amount,
+            ContractError::InsufficientTokens
+        );
+
+        //  charge the smallest gross amount whose net covers the curve's price
+        let gross_amount = gross_for_net(buy_result.sol_amount, global_config.buy_fee_bps())
+            .ok_or(ContractError::ArithmeticError)?;
+        let fee_amount = gross_amount - buy_result.sol_amount;
+        let creator_fee_amount =
+            self.accrue_creator_fee(global_config, fee_amount, global_config.buy_fee_bps())?;
+
+        Ok(SwapQuote {
+            token_amount: buy_result.token_amount,
+            sol_amount: buy_result.sol_amount,
+            fee_amount,
+            creator_fee_amount,
+            referral_fee_amount: 0,
+            completes_curve: self.is_completed,
+        })
+    }
 
-            sol_transfer_from_user(&user, team_wallet.clone(), &system_program, fee_amount)?;
-            amount_out = buy_result.sol_amount;
+    fn apply_sell_exact_out_with_fee(
+        &mut self,
+        global_config: &Config,
+        sol_amount: u64,
+    ) -> Result<SwapQuote> {
+        if sol_amount == 0 {
+            return err!(ContractError::InvalidAmount);
         }
 
-        Ok(amount_out)
+        //  sell enough tokens for the curve to pay out `sol_amount` after the fee
+        let gross_amount = gross_for_net(sol_amount, global_config.sell_fee_bps())
+            .ok_or(ContractError::ArithmeticError)?;
+        let token_amount = self
+            .get_tokens_for_sell_sol(gross_amount)
+            .ok_or(ContractError::InsufficientSol)?;
+
+        let quote = self.apply_sell_with_fee(global_config, token_amount)?;
+        require!(
+            quote.sol_amount - quote.fee_amount >= sol_amount,
+            ContractError::SellFailed
+        );
+
+        Ok(quote)
+    }
+
+    fn apply_initial_buy(
+        &mut self,
+        global_config: &Config,
+        initial_buy: InitialBuy,
+    ) -> Result<SwapQuote> {
+        let fee_split = global_config
+            .buy_fee(initial_buy.lamports)
+            .ok_or(ContractError::ArithmeticError)?;
+        let capped = self
+            .get_tokens_for_buy_sol(fee_split.net)
+            .is_some_and(|tokens| tokens > initial_buy.max_tokens_out);
+
+        let quote = if capped {
+            self.apply_buy_exact_out_with_fee(global_config, initial_buy.max_tokens_out)?
+        } else {
+            self.apply_buy_with_fee(global_config, initial_buy.lamports)?
+        };
+        require!(
+            quote.amount_paid(Direction::Buy) <= initial_buy.lamports,
+            ContractError::PayAmountTooLarge
+        );
+
+        Ok(quote)
     }
 
     fn get_sol_for_sell_tokens(&self, token_amount: u64) -> Option<u64> {
@@ -186,20 +806,27 @@ impl<'info> BondingCurveAccount<'info> for Account<'info, BondingCurve> {
             return None;
         }
 
-        // Convert to common decimal basis (using 9 decimals as base)
+        if self.curve_kind != CurveKind::ConstantProduct {
+            let tokens_sold = self.tokens_sold()?;
+            return self.curve_kind.cost(
+                tokens_sold.checked_sub(token_amount)?,
+                tokens_sold,
+                self.decimals,
+                Rounding::Down,
+            );
+        }
+
+        // Scale tokens up to lamport precision so both reserves share a decimal basis
+        let precision = token_precision(self.decimals)?;
         let current_sol = self.virtual_sol_reserves as u128;
-        let current_tokens = (self.virtual_token_reserves as u128)
-            .checked_mul(1_000_000_000)? // Scale tokens up to 9 decimals
-            .checked_div(1_000_000)?; // From 6 decimals
+        let current_tokens = (self.virtual_token_reserves as u128).checked_mul(precision)?;
 
         // Calculate new reserves using constant product formula
-        let new_tokens = current_tokens.checked_add(
-            (token_amount as u128)
-                .checked_mul(1_000_000_000)? // Scale input tokens to 9 decimals
-                .checked_div(1_000_000)?, // From 6 decimals
-        )?;
+        let new_tokens =
+            current_tokens.checked_add((token_amount as u128).checked_mul(precision)?)?;
 
-        let new_sol = (current_sol.checked_mul(current_tokens)?).checked_div(new_tokens)?;
+        // Round the remaining reserve up so the product never shrinks
+        let new_sol = ceil_div(current_sol.checked_mul(current_tokens)?, new_tokens)?;
 
         let sol_out = current_sol.checked_sub(new_sol)?;
 
@@ -211,52 +838,123 @@ impl<'info> BondingCurveAccount<'info> for Account<'info, BondingCurve> {
             return None;
         }
 
-        // Convert to common decimal basis (using 9 decimals as base)
+        if self.curve_kind != CurveKind::ConstantProduct {
+            return self.curve_kind.tokens_for_sol(
+                self.tokens_sold()?,
+                self.real_token_reserves,
+                sol_amount,
+                self.decimals,
+            );
+        }
+
+        // Scale tokens up to lamport precision so both reserves share a decimal basis
+        let precision = token_precision(self.decimals)?;
         let current_sol = self.virtual_sol_reserves as u128;
-        let current_tokens = (self.virtual_token_reserves as u128)
-            .checked_mul(1_000_000_000)? // Scale tokens up to 9 decimals
-            .checked_div(1_000_000)?; // From 6 decimals
+        let current_tokens = (self.virtual_token_reserves as u128).checked_mul(precision)?;
 
         // Calculate new reserves using constant product formula
         let new_sol = current_sol.checked_add(sol_amount as u128)?;
-        let new_tokens = (current_sol.checked_mul(current_tokens)?).checked_div(new_sol)?;
 
-        let tokens_out = current_tokens.checked_sub(new_tokens)?;
+        // Round the remaining reserve up so the product never shrinks
+        let new_tokens = ceil_div(current_sol.checked_mul(current_tokens)?, new_sol)?;
 
-        // Convert back to 6 decimal places for tokens
-        let tokens_out = tokens_out
-            .checked_mul(1_000_000)? // Convert to 6 decimals
-            .checked_div(1_000_000_000)?; // From 9 decimals
+        // Convert back to the mint's decimals, rounding down
+        let tokens_out = current_tokens
+            .checked_sub(new_tokens)?
+            .checked_div(precision)?;
 
         <u128 as TryInto<u64>>::try_into(tokens_out).ok()
     }
 
-    fn apply_buy(&mut self, mut sol_amount: u64) -> Option<BuyResult> {
+    fn get_sol_for_buy_tokens(&self, token_amount: u64) -> Option<u64> {
+        if token_amount == 0 {
+            return None;
+        }
+
+        if self.curve_kind != CurveKind::ConstantProduct {
+            let tokens_sold = self.tokens_sold()?;
+            return self.curve_kind.cost(
+                tokens_sold,
+                tokens_sold.checked_add(token_amount)?,
+                self.decimals,
+                Rounding::Up,
+            );
+        }
+
+        // Scale tokens up to lamport precision so both reserves share a decimal basis
+        let precision = token_precision(self.decimals)?;
+        let current_sol = self.virtual_sol_reserves as u128;
+        let current_tokens = (self.virtual_token_reserves as u128).checked_mul(precision)?;
+
+        // Calculate new reserves using constant product formula
+        let new_tokens =
+            current_tokens.checked_sub((token_amount as u128).checked_mul(precision)?)?;
+        if new_tokens == 0 {
+            return None;
+        }
+
+        // Round the new reserve up so the buyer never underpays
+        let new_sol = ceil_div(current_sol.checked_mul(current_tokens)?, new_tokens)?;
+
+        let sol_in = new_sol.checked_sub(current_sol)?;
+
+        <u128 as TryInto<u64>>::try_into(sol_in).ok()
+    }
+
+    fn get_tokens_for_sell_sol(&self, sol_amount: u64) -> Option<u64> {
+        if sol_amount == 0 {
+            return None;
+        }
+
+        if self.curve_kind != CurveKind::ConstantProduct {
+            return self
+                .curve_kind
+                .tokens_for_sol_out(self.tokens_sold()?, sol_amount, self.decimals);
+        }
+
+        // Scale tokens up to lamport precision so both reserves share a decimal basis
+        let precision = token_precision(self.decimals)?;
+        let current_sol = self.virtual_sol_reserves as u128;
+        let current_tokens = (self.virtual_token_reserves as u128).checked_mul(precision)?;
+
+        // Calculate new reserves using constant product formula
+        let new_sol = current_sol.checked_sub(sol_amount as u128)?;
+        if new_sol == 0 {
+            return None;
+        }
+
+        // Round the new reserve up so the seller never sells too few tokens
+        let new_tokens = ceil_div(current_sol.checked_mul(current_tokens)?, new_sol)?;
+
+        // Convert back to the mint's decimals, rounding up
+        let tokens_in = ceil_div(new_tokens.checked_sub(current_tokens)?, precision)?;
+
+        <u128 as TryInto<u64>>::try_into(tokens_in).ok()
+    }
+
+    fn apply_buy(&mut self, mut sol_amount: u64, sol_target: Option<u64>) -> Option<BuyResult> {
+        // Clamp to the sol target, the caller keeps whatever is left over
+        if let Some(sol_target) = sol_target {
+            let remaining_sol = sol_target.saturating_sub(self.real_sol_reserves);
+            if sol_amount >= remaining_sol {
+                sol_amount = remaining_sol;
+                self.is_completed = true;
+            }
+        }
 
         // Computing Token Amount out
-        let mut token_amount = self.get_tokens_for_buy_sol(sol_amount)?;
+        let mut token_amount = if sol_amount == 0 {
+            0
+        } else {
+            self.get_tokens_for_buy_sol(sol_amount)?
+        };
 
         if token_amount >= self.real_token_reserves {
             // Last Buy
             token_amount = self.real_token_reserves;
 
-            // Temporarily store the current state
-            let current_virtual_token_reserves = self.virtual_token_reserves;
-            let current_virtual_sol_reserves = self.virtual_sol_reserves;
-
-            // Update self with the new token amount
-            self.virtual_token_reserves = (current_virtual_token_reserves as u128)
-                .checked_sub(token_amount as u128)?
-                .try_into()
-                .ok()?;
-            self.virtual_sol_reserves = 57_502_679_529; // Total raise amount at end
-
-            let recomputed_sol_amount = self.get_sol_for_sell_tokens(token_amount)?;
-            sol_amount = recomputed_sol_amount;
-
-            // Restore the state with the recomputed sol_amount
-            self.virtual_token_reserves = current_virtual_token_reserves;
-            self.virtual_sol_reserves = current_virtual_sol_reserves;
+            // Charge exactly what the remaining tokens cost on this curve
+            sol_amount = self.get_sol_for_buy_tokens(token_amount)?;
 
             // Set complete to true
             self.is_completed = true;
@@ -315,7 +1013,7 @@ impl<'info> BondingCurveAccount<'info> for Account<'info, BondingCurve> {
         self.virtual_token_reserves = new_virtual_token_reserves.try_into().ok()?;
         self.real_token_reserves = new_real_token_reserves.try_into().ok()?;
         self.virtual_sol_reserves = new_virtual_sol_reserves.try_into().ok()?;
-        self.real_sol_reserves = new_real_sol_reserves.try_into().ok()?;
+        self.real_sol_reserves = new_real_sol_reserves;
 
         Some(SellResult {
             token_amount,
@@ -323,3 +1021,633 @@ impl<'info> BondingCurveAccount<'info> for Account<'info, BondingCurve> {
         })
     }
 }
+
+//  multiplier that brings a token amount with `decimals` up to lamport precision
+fn token_precision(decimals: u8) -> Option<u128> {
+    10u128.checked_pow(LAMPORT_DECIMALS.saturating_sub(decimals) as u32)
+}
+
+fn ceil_div(numerator: u128, denominator: u128) -> Option<u128> {
+    numerator
+        .checked_add(denominator.checked_sub(1)?)?
+        .checked_div(denominator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{
+        CONFIG_TOKEN_DECIMALS, INITIAL_REAL_TOKEN_RESERVES, INITIAL_VIRTUAL_SOL_RESERVES,
+        INITIAL_VIRTUAL_TOKEN_RESERVES,
+    };
+    use crate::state::config::tests::config_with_fees;
+    use anchor_lang::Discriminator;
+
+    //  `BondingCurveAccount` is implemented for `Account`, so tests back one with leaked memory
+    fn curve_account(curve: BondingCurve) -> Account<'static, BondingCurve> {
+        let mut data = BondingCurve::DISCRIMINATOR.to_vec();
+        curve.serialize(&mut data).unwrap();
+
+        let info = Box::leak(Box::new(AccountInfo::new(
+            Box::leak(Box::new(Pubkey::new_unique())),
+            false,
+            true,
+            Box::leak(Box::new(0)),
+            Box::leak(data.into_boxed_slice()),
+            Box::leak(Box::new(crate::ID)),
+            false,
+            0,
+        )));
+        Account::try_from(&*info).unwrap()
+    }
+
+    fn default_curve(decimals: u8) -> Account<'static, BondingCurve> {
+        let scale = |amount| scale_token_amount(amount, CONFIG_TOKEN_DECIMALS, decimals).unwrap();
+
+        curve_account(BondingCurve {
+            version: BONDING_CURVE_VERSION,
+            token_mint: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            init_lamport: INITIAL_VIRTUAL_SOL_RESERVES,
+            token_total_supply: scale(1_000_000_000_000_000),
+            virtual_sol_reserves: INITIAL_VIRTUAL_SOL_RESERVES,
+            virtual_token_reserves: scale(INITIAL_VIRTUAL_TOKEN_RESERVES),
+            real_sol_reserves: 0,
+            real_token_reserves: scale(INITIAL_REAL_TOKEN_RESERVES),
+            is_completed: false,
+            has_sol_vault: true,
+            decimals,
+            curve_kind: CurveKind::ConstantProduct,
+            initial_real_token_reserves: scale(INITIAL_REAL_TOKEN_RESERVES),
+            is_frozen: false,
+            start_time: 0,
+            presale_end_time: 0,
+            creator_fees: 0,
+            migrated: false,
+            pending_creator: Pubkey::default(),
+            reserved: [0; BONDING_CURVE_RESERVED],
+        })
+    }
+
+    //  deterministic xorshift so the property tests need no extra dependency
+    fn sol_amounts(seed: u64) -> impl Iterator<Item = u64> {
+        let mut state = seed;
+        std::iter::from_fn(move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            Some(state % 50_000_000_000 + 1)
+        })
+        .take(200)
+    }
+
+    #[test]
+    fn buy_output_matches_exact_price_for_every_decimals() {
+        for decimals in 0..=9u8 {
+            let curve = default_curve(decimals);
+            let sol_reserves = curve.virtual_sol_reserves as u128;
+            let token_reserves = curve.virtual_token_reserves as u128;
+
+            for sol_amount in sol_amounts(decimals as u64 + 1) {
+                let tokens_out = curve.get_tokens_for_buy_sol(sol_amount).unwrap() as u128;
+
+                //  tokens_out == floor(token_reserves * sol / (sol_reserves + sol)), off by at most one
+                let new_sol = sol_reserves + sol_amount as u128;
+                let exact = token_reserves * sol_amount as u128;
+                assert!(tokens_out * new_sol <= exact, "decimals {decimals}");
+                assert!(
+                    exact - tokens_out * new_sol <= 2 * new_sol,
+                    "decimals {decimals}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn price_is_the_same_in_whole_tokens_across_decimals() {
+        let reference = default_curve(CONFIG_TOKEN_DECIMALS);
+
+        for decimals in 0..=9u8 {
+            let curve = default_curve(decimals);
+
+            for sol_amount in sol_amounts(42) {
+                let expected = scale_token_amount(
+                    reference.get_tokens_for_buy_sol(sol_amount).unwrap(),
+                    CONFIG_TOKEN_DECIMALS,
+                    decimals,
+                )
+                .unwrap();
+                let tokens_out = curve.get_tokens_for_buy_sol(sol_amount).unwrap();
+
+                //  rescaling the 6 decimal reference loses at most one unit per extra digit
+                let tolerance = 10u64.pow(decimals.saturating_sub(CONFIG_TOKEN_DECIMALS) as u32);
+                assert!(
+                    tokens_out.abs_diff(expected) <= tolerance,
+                    "decimals {decimals}: {tokens_out} vs {expected}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip_never_returns_more_sol() {
+        for decimals in 0..=9u8 {
+            for sol_amount in sol_amounts(decimals as u64 + 7) {
+                let mut curve = default_curve(decimals);
+                let product_before =
+                    curve.virtual_sol_reserves as u128 * curve.virtual_token_reserves as u128;
+
+                let buy = curve.apply_buy(sol_amount, None).unwrap();
+                assert!(
+                    curve.virtual_sol_reserves as u128 * curve.virtual_token_reserves as u128
+                        >= product_before
+                );
+                if curve.is_completed || buy.token_amount == 0 {
+                    continue;
+                }
+
+                let sell = curve.apply_sell(buy.token_amount).unwrap();
+                assert!(sell.sol_amount <= buy.sol_amount, "decimals {decimals}");
+                assert!(
+                    curve.virtual_sol_reserves as u128 * curve.virtual_token_reserves as u128
+                        >= product_before
+                );
+            }
+        }
+    }
+
+    fn custom_curve(
+        virtual_sol: u64,
+        virtual_tokens: u64,
+        real_tokens: u64,
+    ) -> Account<'static, BondingCurve> {
+        let mut curve = default_curve(CONFIG_TOKEN_DECIMALS);
+        curve.virtual_sol_reserves = virtual_sol;
+        curve.virtual_token_reserves = virtual_tokens;
+        curve.real_token_reserves = real_tokens;
+        curve
+    }
+
+    #[test]
+    fn default_curve_completes_at_the_legacy_raise() {
+        let mut curve = default_curve(CONFIG_TOKEN_DECIMALS);
+        curve.apply_buy(u64::MAX / 2, None).unwrap();
+
+        assert!(curve.is_completed);
+        assert_eq!(curve.real_token_reserves, 0);
+        //  the constant this replaces: k / (virtual tokens - real tokens)
+        assert_eq!(curve.virtual_sol_reserves, 57_502_679_529);
+    }
+
+    #[test]
+    fn any_configured_curve_completes_at_a_consistent_price() {
+        let configs = [
+            (15_000_000_000, 1_073_000_000_000_000, 793_100_000_000_000),
+            (30_000_000_000, 1_073_000_000_000_000, 793_100_000_000_000),
+            (15_000_000_000, 1_073_000_000_000_000, 500_000_000_000_000),
+            (5_000_000_000, 2_000_000_000_000_000, 1_900_000_000_000_000),
+        ];
+
+        for (virtual_sol, virtual_tokens, real_tokens) in configs {
+            //  final virtual sol the constant product implies once every real token is sold
+            let product = virtual_sol as u128 * virtual_tokens as u128;
+            let final_sol =
+                ceil_div(product, (virtual_tokens - real_tokens) as u128).unwrap() as u64;
+
+            //  completing in one shot
+            let mut curve = custom_curve(virtual_sol, virtual_tokens, real_tokens);
+            let buy = curve.apply_buy(u64::MAX / 2, None).unwrap();
+            assert!(curve.is_completed);
+            assert_eq!(buy.token_amount, real_tokens);
+            assert_eq!(curve.virtual_sol_reserves, final_sol);
+            assert_eq!(curve.real_sol_reserves, final_sol - virtual_sol);
+
+            //  completing after many smaller buys lands on the same price
+            let mut curve = custom_curve(virtual_sol, virtual_tokens, real_tokens);
+            for sol_amount in sol_amounts(virtual_sol) {
+                curve.apply_buy(sol_amount / 10, None).unwrap();
+                if curve.is_completed {
+                    break;
+                }
+            }
+            if !curve.is_completed {
+                curve.apply_buy(u64::MAX / 2, None).unwrap();
+            }
+            assert_eq!(curve.real_token_reserves, 0);
+            assert!(curve.virtual_sol_reserves >= final_sol);
+            //  per-buy rounding in the curve's favour is at most a few lamports per trade
+            assert!(curve.virtual_sol_reserves - final_sol <= 1_000);
+        }
+    }
+
+    #[test]
+    fn sol_target_clamps_the_last_buy_to_hit_it_exactly() {
+        let sol_target = 20_000_000_000;
+        let mut curve = default_curve(CONFIG_TOKEN_DECIMALS);
+
+        let first = curve.apply_buy(15_000_000_000, Some(sol_target)).unwrap();
+        assert!(!curve.is_completed);
+        assert_eq!(first.sol_amount, 15_000_000_000);
+
+        //  offering more than what is left only takes the remainder
+        let last = curve.apply_buy(10_000_000_000, Some(sol_target)).unwrap();
+        assert!(curve.is_completed);
+        assert_eq!(last.sol_amount, 5_000_000_000);
+        assert_eq!(curve.real_sol_reserves, sol_target);
+        assert!(curve.real_token_reserves > 0);
+    }
+
+    #[test]
+    fn token_exhaustion_still_completes_below_the_sol_target() {
+        let mut curve = default_curve(CONFIG_TOKEN_DECIMALS);
+        let buy = curve
+            .apply_buy(u64::MAX / 2, Some(100_000_000_000))
+            .unwrap();
+
+        assert!(curve.is_completed);
+        assert_eq!(curve.real_token_reserves, 0);
+        assert_eq!(
+            buy.sol_amount,
+            57_502_679_529 - INITIAL_VIRTUAL_SOL_RESERVES
+        );
+    }
+
+    #[test]
+    fn other_curve_kinds_complete_at_the_cost_of_the_whole_curve() {
+        let linear = CurveKind::Linear {
+            start_price: 14_000_000_000,
+            slope: 100,
+        };
+        let mut curve = default_curve(CONFIG_TOKEN_DECIMALS);
+        curve.curve_kind = linear;
+        curve.virtual_sol_reserves = 0;
+        curve.virtual_token_reserves = curve.real_token_reserves;
+
+        let whole_curve = linear
+            .cost(0, curve.initial_real_token_reserves, CONFIG_TOKEN_DECIMALS, Rounding::Up)
+            .unwrap();
+
+        let mut paid = 0;
+        let mut buys = 0;
+        for sol_amount in sol_amounts(99) {
+            let buy = curve.apply_buy(sol_amount / 10, None).unwrap();
+            paid += buy.sol_amount;
+            buys += 1;
+
+            //  selling straight back never returns more than was paid
+            let mut probe = curve.clone();
+            if buy.token_amount > 0 {
+                assert!(probe.apply_sell(buy.token_amount).unwrap().sol_amount <= buy.sol_amount);
+            }
+            if curve.is_completed {
+                break;
+            }
+        }
+        assert!(curve.is_completed);
+        assert_eq!(curve.real_token_reserves, 0);
+        assert_eq!(curve.real_sol_reserves, paid);
+        assert!(paid >= whole_curve - buys && paid <= whole_curve + buys);
+    }
+
+    #[test]
+    fn quoting_a_copy_matches_the_trade_and_leaves_the_curve_untouched() {
+        let config = config_with_fees(69, 69);
+        let mut curve = default_curve(CONFIG_TOKEN_DECIMALS);
+
+        for sol_amount in sol_amounts(5).take(20) {
+            let reserves = (curve.virtual_sol_reserves, curve.real_token_reserves);
+            let quote = curve.clone().apply_buy_with_fee(&config, sol_amount).unwrap();
+            assert_eq!(reserves, (curve.virtual_sol_reserves, curve.real_token_reserves));
+
+            let trade = curve.apply_buy_with_fee(&config, sol_amount).unwrap();
+            assert_eq!(quote, trade);
+            if curve.is_completed {
+                break;
+            }
+
+            let quote = curve.clone().apply_sell_with_fee(&config, trade.token_amount / 2).unwrap();
+            let trade = curve.apply_sell_with_fee(&config, trade.token_amount / 2).unwrap();
+            assert_eq!(quote, trade);
+        }
+    }
+
+    #[test]
+    fn creator_fees_accrue_on_every_kind_of_trade() {
+        let mut config = config_with_fees(100, 100);
+        config.creator_fee_bps = 50;
+        let mut curve = default_curve(CONFIG_TOKEN_DECIMALS);
+
+        let buy = curve.apply_buy_with_fee(&config, 1_000_000_000).unwrap();
+        assert_eq!(buy.fee_amount, 15_000_000);
+        assert_eq!(buy.creator_fee_amount, 5_000_000);
+        assert_eq!(buy.sol_amount + buy.fee_amount, 1_000_000_000);
+
+        let sell = curve.apply_sell_with_fee(&config, buy.token_amount / 2).unwrap();
+        assert_eq!(sell.creator_fee_amount * 3, sell.fee_amount - sell.fee_amount % 3);
+
+        let exact_buy = curve.apply_buy_exact_out_with_fee(&config, 1_000_000).unwrap();
+        let exact_sell = curve.apply_sell_exact_out_with_fee(&config, 100_000_000).unwrap();
+        assert_eq!(
+            curve.creator_fees,
+            buy.creator_fee_amount
+                + sell.creator_fee_amount
+                + exact_buy.creator_fee_amount
+                + exact_sell.creator_fee_amount
+        );
+        assert!(exact_sell.sol_amount - exact_sell.fee_amount >= 100_000_000);
+
+        //  without a creator fee the platform keeps the whole fee
+        let config = config_with_fees(100, 100);
+        let quote = curve.apply_buy_with_fee(&config, 1_000_000_000).unwrap();
+        assert_eq!(quote.creator_fee_amount, 0);
+        assert_eq!(quote.fee_amount, 10_000_000);
+    }
+
+    #[test]
+    fn curves_migrate_once_and_spend_their_whole_raise() {
+        let mut curve = default_curve(CONFIG_TOKEN_DECIMALS);
+        curve.real_sol_reserves = 85_000_000_000;
+        curve.creator_fees = 1_000_000;
+        assert!(curve.migrate(80_000_000_000, 500_000_000).is_err());
+
+        curve.is_completed = true;
+        assert!(curve.migrate(85_000_000_000, 1).is_err());
+        assert_eq!(curve.migrate(80_000_000_000, 500_000_000).unwrap(), 4_500_000_000);
+        assert_eq!(curve.real_sol_reserves, 0);
+        assert_eq!(curve.creator_fees, 1_000_000);
+        assert!(curve.migrated);
+
+        //  a second `create_pool` would respend the vault
+        curve.real_sol_reserves = 85_000_000_000;
+        assert!(curve.migrate(80_000_000_000, 500_000_000).is_err());
+    }
+
+    #[test]
+    fn creator_role_moves_only_once_accepted() {
+        let mut curve = default_curve(CONFIG_TOKEN_DECIMALS);
+        let creator = curve.creator;
+        let new_creator = Pubkey::new_unique();
+
+        assert!(curve.propose_creator(Pubkey::default()).is_err());
+        assert!(curve.accept_creator(new_creator).is_err());
+        assert!(curve.cancel_creator_transfer().is_err());
+
+        curve.propose_creator(new_creator).unwrap();
+        assert_eq!(curve.creator, creator);
+        assert!(curve.accept_creator(Pubkey::new_unique()).is_err());
+        assert_eq!(curve.cancel_creator_transfer().unwrap(), new_creator);
+        assert!(curve.accept_creator(new_creator).is_err());
+
+        curve.propose_creator(new_creator).unwrap();
+        assert_eq!(curve.accept_creator(new_creator).unwrap(), creator);
+        assert_eq!(curve.creator, new_creator);
+        assert_eq!(curve.pending_creator, Pubkey::default());
+    }
+
+    #[test]
+    fn initial_buys_stop_at_the_token_cap() {
+        let config = config_with_fees(69, 69);
+        let curve = default_curve(CONFIG_TOKEN_DECIMALS);
+
+        let uncapped = InitialBuy {
+            lamports: 1_000_000_000,
+            max_tokens_out: u64::MAX,
+        };
+        let quote = curve.clone().apply_initial_buy(&config, uncapped).unwrap();
+        assert_eq!(quote, curve.clone().apply_buy_with_fee(&config, 1_000_000_000).unwrap());
+
+        let capped = InitialBuy {
+            max_tokens_out: quote.token_amount / 2,
+            ..uncapped
+        };
+        let capped_quote = curve.clone().apply_initial_buy(&config, capped).unwrap();
+        assert!(capped_quote.token_amount >= capped.max_tokens_out);
+        assert!(capped_quote.token_amount < quote.token_amount);
+        assert!(capped_quote.amount_paid(Direction::Buy) < capped.lamports);
+    }
+
+    #[test]
+    fn exact_out_trades_deliver_at_least_what_was_asked() {
+        let config = config_with_fees(69, 100);
+
+        for decimals in [0, CONFIG_TOKEN_DECIMALS, 9] {
+            let mut curve = default_curve(decimals);
+            curve.apply_buy(20_000_000_000, None).unwrap();
+            let unit = 10u64.pow(decimals as u32);
+
+            for sol_amount in sol_amounts(decimals as u64 + 11).take(50) {
+                //  buy exactly this many tokens, costing no more than an exact-in buy of the same SOL
+                let token_amount = (sol_amount % 100_000_000 + 1) * unit;
+                let quote = curve
+                    .clone()
+                    .apply_buy_exact_out_with_fee(&config, token_amount)
+                    .unwrap();
+                assert!(quote.token_amount >= token_amount);
+                let exact_in = curve
+                    .clone()
+                    .apply_buy_with_fee(&config, quote.sol_amount + quote.fee_amount)
+                    .unwrap();
+                assert!(exact_in.token_amount >= token_amount);
+                //  paying a lamport less would not have bought them
+                let cheaper = curve
+                    .clone()
+                    .apply_buy_with_fee(&config, quote.sol_amount + quote.fee_amount - 1)
+                    .unwrap();
+                assert!(cheaper.token_amount < token_amount);
+
+                //  sell for at least this much SOL after the fee
+                let sol_out = sol_amount % 1_000_000_000 + 1;
+                let quote = curve
+                    .clone()
+                    .apply_sell_exact_out_with_fee(&config, sol_out)
+                    .unwrap();
+                assert!(quote.sol_amount - quote.fee_amount >= sol_out);
+                let fewer = curve
+                    .clone()
+                    .apply_sell_with_fee(&config, quote.token_amount - 1)
+                    .map(|quote| quote.sol_amount - quote.fee_amount);
+                //  and one token less would not have been enough
+                assert!(fewer.map_or(true, |net| net < sol_out));
+            }
+        }
+    }
+
+    #[test]
+    fn quotes_and_trades_share_the_curve_guards() {
+        let config = config_with_fees(100, 100);
+        let curve = default_curve(9);
+        let tradable = |curve: &BondingCurve, config: &Config, direction| {
+            curve.require_tradable(config, direction, 1_000)
+        };
+        assert_eq!(tradable(&curve, &config, Direction::Buy).unwrap(), LaunchPhase::Public);
+
+        //  pausing buys leaves sells open
+        let mut paused = config.clone();
+        paused.paused = PAUSE_BUY;
+        assert_eq!(
+            tradable(&curve, &paused, Direction::Buy).unwrap_err(),
+            ContractError::Paused.into()
+        );
+        assert!(tradable(&curve, &paused, Direction::Sell).is_ok());
+
+        let (mut frozen, mut completed, mut legacy, mut scheduled) =
+            (default_curve(9), default_curve(9), default_curve(9), default_curve(9));
+        frozen.is_frozen = true;
+        completed.is_completed = true;
+        legacy.has_sol_vault = false;
+        scheduled.start_time = 1_001;
+        for (curve, error) in [
+            (frozen, ContractError::CurveFrozen),
+            (completed, ContractError::CurveAlreadyCompleted),
+            (legacy, ContractError::SolVaultNotInitialized),
+            (scheduled, ContractError::IncorrectLaunchPhase),
+        ] {
+            for direction in [Direction::Buy, Direction::Sell] {
+                assert_eq!(tradable(&curve, &config, direction).unwrap_err(), error.into());
+            }
+        }
+
+        let mut presale = default_curve(9);
+        presale.presale_end_time = 1_001;
+        assert_eq!(tradable(&presale, &config, Direction::Buy).unwrap(), LaunchPhase::Presale);
+    }
+
+    #[test]
+    fn referred_quotes_split_the_platform_fee() {
+        let mut config = config_with_fees(100, 100);
+        config.creator_fee_bps = 50;
+        config.referral_share_bps = 2_000;
+
+        let mut quote = default_curve(9).apply_buy_with_fee(&config, 1_000_000_000).unwrap();
+        assert_eq!(quote.referral_fee_amount, 0);
+        assert_eq!(quote.team_fee_amount(), quote.platform_fee_amount());
+
+        quote.refer(&config).unwrap();
+        assert_eq!(quote.platform_fee_amount(), 10_000_000);
+        assert_eq!(quote.referral_fee_amount, 2_000_000);
+        assert_eq!(
+            quote.team_fee_amount() + quote.referral_fee_amount + quote.creator_fee_amount,
+            quote.fee_amount
+        );
+    }
+
+    #[test]
+    fn only_known_directions_are_accepted() {
+        assert_eq!(Direction::try_from(0).unwrap(), Direction::Buy);
+        assert_eq!(Direction::try_from(1).unwrap(), Direction::Sell);
+        for direction in 2..=u8::MAX {
+            assert!(Direction::try_from(direction).is_err());
+        }
+
+        //  same wire format as the raw `u8` it replaces
+        assert_eq!(Direction::Buy.try_to_vec().unwrap(), vec![0]);
+        assert_eq!(Direction::Sell.try_to_vec().unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn largest_curve_fills_its_space_exactly() {
+        use crate::state::pricing::{PriceTier, MAX_PRICE_TIERS};
+
+        let mut curve = default_curve(9);
+        curve.curve_kind = CurveKind::Stepwise {
+            tiers: [PriceTier { end: u64::MAX, price: u64::MAX }; MAX_PRICE_TIERS],
+            tier_count: MAX_PRICE_TIERS as u8,
+        };
+
+        let mut data = Vec::new();
+        curve.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), 8 + BondingCurve::INIT_SPACE);
+
+        let mut data = Vec::new();
+        default_curve(9).try_serialize(&mut data).unwrap();
+        assert!(data.len() < 8 + BondingCurve::INIT_SPACE);
+    }
+
+    #[test]
+    fn unversioned_curves_are_detected_and_upgraded() {
+        let curve = default_curve(6);
+        let mint = curve.token_mint;
+
+        let mut current = Vec::new();
+        curve.try_serialize(&mut current).unwrap();
+        assert_eq!(BondingCurve::stored_version(&current, &mint), Some(BONDING_CURVE_VERSION));
+
+        //  the first curves stopped after `is_completed` in a 120 byte account
+        let mut legacy = current.clone();
+        legacy.remove(8);
+        legacy.truncate(8 + 113);
+        legacy.resize(8 + 120, 0);
+        assert_eq!(BondingCurve::stored_version(&legacy, &mint), Some(0));
+
+        legacy.resize(8 + std::mem::size_of::<BondingCurve>(), 0);
+        let upgraded = LegacyBondingCurve::deserialize(&mut &legacy[8..])
+            .unwrap()
+            .into_bonding_curve(6, curve.initial_real_token_reserves);
+        assert!(upgraded.is_current(&mint));
+        assert!(!upgraded.has_sol_vault);
+        assert_eq!(upgraded.curve_kind, CurveKind::ConstantProduct);
+        assert_eq!(upgraded.real_token_reserves, curve.real_token_reserves);
+        assert_eq!(upgraded.decimals, 6);
+
+        assert!(!upgraded.is_current(&Pubkey::new_unique()));
+        assert_eq!(BondingCurve::stored_version(&current[..20], &mint), None);
+    }
+
+    #[test]
+    fn baseline_curves_keep_their_sold_tokens_when_upgraded() {
+        let (mint, creator) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let launch_real_tokens = INITIAL_REAL_TOKEN_RESERVES;
+        let real_tokens = launch_real_tokens - 100_000_000_000_000;
+
+        //  `8 + size_of::<BondingCurve>()` of the baseline layout, fields up to `is_completed`
+        let mut data = BondingCurve::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(mint.as_ref());
+        data.extend_from_slice(creator.as_ref());
+        for field in [
+            INITIAL_VIRTUAL_SOL_RESERVES,
+            1_000_000_000_000_000,
+            INITIAL_VIRTUAL_SOL_RESERVES + 5_000_000_000,
+            INITIAL_VIRTUAL_TOKEN_RESERVES - 100_000_000_000_000,
+            5_000_000_000,
+            real_tokens,
+        ] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.push(0);
+        data.resize(128, 0);
+        assert_eq!(BondingCurve::stored_version(&data, &mint), Some(0));
+
+        data.resize(8 + BondingCurve::INIT_SPACE, 0);
+        let upgraded = LegacyBondingCurve::deserialize(&mut &data[8..])
+            .unwrap()
+            .into_bonding_curve(CONFIG_TOKEN_DECIMALS, launch_real_tokens);
+        assert!(upgraded.is_current(&mint));
+        assert_eq!(upgraded.creator, creator);
+        assert_eq!(upgraded.real_token_reserves, real_tokens);
+        assert_eq!(upgraded.initial_real_token_reserves, launch_real_tokens);
+        assert_eq!(upgraded.tokens_sold(), Some(100_000_000_000_000));
+
+        //  a config lowered since launch never leaves the curve with negative sales
+        let upgraded = LegacyBondingCurve::deserialize(&mut &data[8..])
+            .unwrap()
+            .into_bonding_curve(CONFIG_TOKEN_DECIMALS, real_tokens - 1);
+        assert_eq!(upgraded.tokens_sold(), Some(0));
+    }
+
+    #[test]
+    fn outputs_are_monotonic_and_bounded() {
+        for decimals in 0..=9u8 {
+            let curve = default_curve(decimals);
+            let mut amounts: Vec<u64> = sol_amounts(decimals as u64 + 3).collect();
+            amounts.sort_unstable();
+
+            let mut previous = 0;
+            for sol_amount in amounts {
+                let tokens_out = curve.get_tokens_for_buy_sol(sol_amount).unwrap();
+                assert!(tokens_out >= previous);
+                assert!(tokens_out < curve.virtual_token_reserves);
+                previous = tokens_out;
+            }
+        }
+    }
+}
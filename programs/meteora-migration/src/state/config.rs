@@ -1,24 +1,542 @@
+use crate::constants::{CONFIG_TOKEN_DECIMALS, POOL_CREATION_RESERVE_LAMPORTS};
 use crate::errors::*;
+use crate::fees::{
+    bps_from_percentage, calculate_fee, fee_share, split_fee, FeeSplit, BPS_DENOMINATOR,
+};
+use crate::state::pricing::{CurveKind, Rounding, ALL_CURVE_KINDS};
 use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
 use core::fmt::Debug;
 
+//  bits of `Config.paused`
+pub const PAUSE_CREATE: u8 = 1 << 0;
+pub const PAUSE_BUY: u8 = 1 << 1;
+pub const PAUSE_SELL: u8 = 1 << 2;
+pub const PAUSE_MIGRATE: u8 = 1 << 3;
+pub const ALL_PAUSE_FLAGS: u8 = PAUSE_CREATE | PAUSE_BUY | PAUSE_SELL | PAUSE_MIGRATE;
+
+//  layout written by this program, bumped whenever a field is added to `Config`
+pub const CONFIG_VERSION: u8 = 1;
+pub const CONFIG_RESERVED: usize = 43;
+
 #[account]
-#[derive(Debug)]
+#[derive(InitSpace, Debug)]
 pub struct Config {
+    //  `migrate_config` upgrades configs whose version is below `CONFIG_VERSION`
+    pub version: u8,
+
     pub authority: Pubkey,
     //  use this for meteora migration
     pub migration_authority: Pubkey,
 
+    //  keys proposed to take over the authorities, `Pubkey::default()` when none is pending
+    pub pending_authority: Pubkey,
+    pub pending_migration_authority: Pubkey,
+
+    pub team_wallet: Pubkey,
+    pub migration_wallet: Pubkey,
+
+    pub init_bonding_curve: f64, // bonding curve init percentage. The remaining amount vests to the team wallet for distribution to agent
+
+    pub platform_buy_fee_bps: u16, //  platform fee in basis points
+    pub platform_sell_fee_bps: u16,
+    pub platform_migration_fee_bps: u16,
+
+    pub curve_limit: u64, //  lamports to complete te bonding curve
+    pub complete_on_curve_limit: bool, //  complete once real sol reserves reach `curve_limit`
+
+    pub allowed_curve_kinds: u8, //  bitmask of `CurveKind::mask` creators may launch with
+
+    pub paused: u8, //  bitmask of the `PAUSE_*` actions currently halted
+
+    pub config_change_delay: i64, //  seconds a queued fee or wallet change waits before it can execute
+
+    pub lamport_amount_config: AmountConfig<u64>,
+    pub token_supply_config: AmountConfig<u64>,
+    pub token_decimals_config: AmountConfig<u8>,
+
+    pub initial_virtual_token_reserves_config: u64,
+    pub initial_virtual_sol_reserves_config: u64,
+    pub initial_real_token_reserves_config: u64,
+    pub initial_meteora_token_reserves: u64,
+    pub initial_meteora_sol_amount: u64,
+
+    pub initialized: bool,
+
+    //  only creators with a `WhitelistEntry` may launch while set
+    pub whitelist_enabled: bool,
+
+    //  the team share of each launch unlocks linearly over `team_vesting_duration` seconds
+    //  from the curve's start, none of it before `team_vesting_cliff` seconds have passed
+    pub team_vesting_cliff: i64,
+    pub team_vesting_duration: i64,
+
+    //  charged on top of the platform fees on buys and sells, accrues to the curve's creator
+    pub creator_fee_bps: u16,
+
+    //  slice of the platform fee paid to the referrer of a trade, in bps of that fee
+    pub referral_share_bps: u16,
+
+    //  room for new fields, so they can be added without growing the account.
+    //  fields carved out of it read as zero on configs written before they existed.
+    pub reserved: [u8; CONFIG_RESERVED],
+}
+
+impl Config {
+    //  the config in `data` if it is in the current layout. configs written before layouts
+    //  were versioned have no version byte and fail to parse or read a mismatching version.
+    pub fn current(data: &[u8]) -> Option<Config> {
+        Config::try_deserialize(&mut &data[..])
+            .ok()
+            .filter(|config| config.version == CONFIG_VERSION)
+    }
+
+    //  sol target that completes a curve, if curves complete on `curve_limit`
+    pub fn sol_target(&self) -> Option<u64> {
+        self.complete_on_curve_limit.then_some(self.curve_limit)
+    }
+
+    //  platform and creator fee together
+    pub fn buy_fee_bps(&self) -> u16 {
+        self.platform_buy_fee_bps.saturating_add(self.creator_fee_bps)
+    }
+
+    pub fn sell_fee_bps(&self) -> u16 {
+        self.platform_sell_fee_bps.saturating_add(self.creator_fee_bps)
+    }
+
+    //  fee charged on the SOL a buyer sends in
+    pub fn buy_fee(&self, sol_amount: u64) -> Option<FeeSplit> {
+        split_fee(sol_amount, self.buy_fee_bps())
+    }
+
+    //  fee charged on the SOL a seller takes out
+    pub fn sell_fee(&self, sol_amount: u64) -> Option<FeeSplit> {
+        split_fee(sol_amount, self.sell_fee_bps())
+    }
+
+    //  creator's part of a `fee` charged at `fee_bps`, the platform keeps the rounding
+    pub fn creator_fee(&self, fee: u64, fee_bps: u16) -> Option<u64> {
+        fee_share(fee, self.creator_fee_bps, fee_bps)
+    }
+
+    //  referrer's part of the platform's share of a fee
+    pub fn referral_fee(&self, platform_fee: u64) -> Option<u64> {
+        fee_share(platform_fee, self.referral_share_bps, BPS_DENOMINATOR as u16)
+    }
+
+    pub fn allows_curve_kind(&self, curve_kind: &CurveKind) -> bool {
+        self.allowed_curve_kinds & curve_kind.mask() != 0
+    }
+
+    pub fn require_not_paused(&self, action: u8) -> Result<()> {
+        require!(self.paused & action == 0, ContractError::Paused);
+        Ok(())
+    }
+
+    pub fn authority_of(&self, role: AuthorityRole) -> Pubkey {
+        match role {
+            AuthorityRole::Authority => self.authority,
+            AuthorityRole::MigrationAuthority => self.migration_authority,
+        }
+    }
+
+    pub fn pending_authority_of(&self, role: AuthorityRole) -> Pubkey {
+        match role {
+            AuthorityRole::Authority => self.pending_authority,
+            AuthorityRole::MigrationAuthority => self.pending_migration_authority,
+        }
+    }
+
+    fn pending_authority_mut(&mut self, role: AuthorityRole) -> &mut Pubkey {
+        match role {
+            AuthorityRole::Authority => &mut self.pending_authority,
+            AuthorityRole::MigrationAuthority => &mut self.pending_migration_authority,
+        }
+    }
+
+    //  replaces any earlier proposal for the same role
+    pub fn propose_authority(&mut self, role: AuthorityRole, new_authority: Pubkey) -> Result<()> {
+        require!(new_authority != Pubkey::default(), ContractError::ValueInvalid);
+
+        *self.pending_authority_mut(role) = new_authority;
+        Ok(())
+    }
+
+    //  hands the role to the pending key, returns the key it was taken from
+    pub fn accept_authority(&mut self, role: AuthorityRole, signer: Pubkey) -> Result<Pubkey> {
+        let pending = self.pending_authority_of(role);
+        require!(pending != Pubkey::default(), ContractError::NoPendingAuthority);
+        require!(pending == signer, ContractError::IncorrectAuthority);
+
+        *self.pending_authority_mut(role) = Pubkey::default();
+        let previous = match role {
+            AuthorityRole::Authority => std::mem::replace(&mut self.authority, signer),
+            AuthorityRole::MigrationAuthority => {
+                std::mem::replace(&mut self.migration_authority, signer)
+            }
+        };
+
+        Ok(previous)
+    }
+
+    //  drops the pending proposal, returns the key that was proposed
+    pub fn cancel_authority_transfer(&mut self, role: AuthorityRole) -> Result<Pubkey> {
+        let pending = std::mem::take(self.pending_authority_mut(role));
+        require!(pending != Pubkey::default(), ContractError::NoPendingAuthority);
+
+        Ok(pending)
+    }
+
+    pub fn fee_params(&self) -> FeeParams {
+        FeeParams {
+            platform_buy_fee_bps: self.platform_buy_fee_bps,
+            platform_sell_fee_bps: self.platform_sell_fee_bps,
+            platform_migration_fee_bps: self.platform_migration_fee_bps,
+            creator_fee_bps: self.creator_fee_bps,
+            referral_share_bps: self.referral_share_bps,
+        }
+    }
+
+    pub fn wallet_params(&self) -> WalletParams {
+        WalletParams {
+            team_wallet: self.team_wallet,
+            migration_wallet: self.migration_wallet,
+        }
+    }
+
+    pub fn curve_params(&self) -> CurveParams {
+        CurveParams {
+            init_bonding_curve: self.init_bonding_curve,
+            curve_limit: self.curve_limit,
+            complete_on_curve_limit: self.complete_on_curve_limit,
+            allowed_curve_kinds: self.allowed_curve_kinds,
+            initial_virtual_token_reserves_config: self.initial_virtual_token_reserves_config,
+            initial_virtual_sol_reserves_config: self.initial_virtual_sol_reserves_config,
+            initial_real_token_reserves_config: self.initial_real_token_reserves_config,
+            initial_meteora_token_reserves: self.initial_meteora_token_reserves,
+            initial_meteora_sol_amount: self.initial_meteora_sol_amount,
+            team_vesting_cliff: self.team_vesting_cliff,
+            team_vesting_duration: self.team_vesting_duration,
+        }
+    }
+
+    pub fn launch_limits(&self) -> LaunchLimits {
+        LaunchLimits {
+            lamport_amount_config: self.lamport_amount_config.clone(),
+            token_supply_config: self.token_supply_config.clone(),
+            token_decimals_config: self.token_decimals_config.clone(),
+        }
+    }
+
+    //  checks every group on its own, then the invariants that span groups
+    pub fn validate(&self) -> Result<()> {
+        self.fee_params().validate()?;
+        self.wallet_params().validate()?;
+        let curve_params = self.curve_params();
+        curve_params.validate()?;
+        self.launch_limits().validate()?;
+        require!(
+            self.paused & !ALL_PAUSE_FLAGS == 0,
+            ContractError::InvalidPauseFlags
+        );
+        require!(
+            self.config_change_delay >= 0,
+            ContractError::InvalidConfigChangeDelay
+        );
+
+        //  the curve and the meteora pool are both funded out of the curve's share of the supply
+        let reserved_tokens = (self.initial_real_token_reserves_config as u128)
+            .checked_add(self.initial_meteora_token_reserves as u128)
+            .ok_or(ContractError::ArithmeticError)?;
+        if let Some(largest_supply) = self.token_supply_config.largest() {
+            let largest_supply =
+                *largest_supply as u128 * 10u128.pow(CONFIG_TOKEN_DECIMALS as u32);
+            require!(
+                reserved_tokens <= curve_params.curve_tokens(largest_supply),
+                ContractError::ReservesExceedSupply
+            );
+        }
+
+        //  the other kinds raise what their own prices add up to, they are checked at launch
+        if self.allows_curve_kind(&CurveKind::ConstantProduct) {
+            let completed_sol = curve_params
+                .completed_curve_sol(
+                    &CurveKind::ConstantProduct,
+                    self.initial_real_token_reserves_config,
+                    CONFIG_TOKEN_DECIMALS,
+                )
+                .ok_or(ContractError::ArithmeticError)?;
+            self.require_raise_funds_pool(completed_sol)?;
+        }
+
+        Ok(())
+    }
+
+    //  `create_pool` deposits the meteora sol out of what is left after the migration fee
+    pub fn require_raise_funds_pool(&self, completed_sol: u64) -> Result<()> {
+        let migration_fee = calculate_fee(completed_sol, self.platform_migration_fee_bps)
+            .ok_or(ContractError::ArithmeticError)?;
+        let pool_sol = completed_sol
+            .saturating_sub(migration_fee)
+            .saturating_sub(POOL_CREATION_RESERVE_LAMPORTS);
+        require!(
+            self.initial_meteora_sol_amount <= pool_sol,
+            ContractError::MeteoraSolExceedsRaise
+        );
+
+        Ok(())
+    }
+
+    //  validates and writes one group of settings, returns the group as it was before
+    pub fn apply_update(&mut self, update: ConfigUpdate) -> Result<ConfigUpdate> {
+        let previous = match update {
+            ConfigUpdate::Fees(fees) => {
+                fees.validate()?;
+                let previous = self.fee_params();
+                self.platform_buy_fee_bps = fees.platform_buy_fee_bps;
+                self.platform_sell_fee_bps = fees.platform_sell_fee_bps;
+                self.platform_migration_fee_bps = fees.platform_migration_fee_bps;
+                self.creator_fee_bps = fees.creator_fee_bps;
+                self.referral_share_bps = fees.referral_share_bps;
+                ConfigUpdate::Fees(previous)
+            }
+            ConfigUpdate::Wallets(wallets) => {
+                wallets.validate()?;
+                let previous = self.wallet_params();
+                self.team_wallet = wallets.team_wallet;
+                self.migration_wallet = wallets.migration_wallet;
+                ConfigUpdate::Wallets(previous)
+            }
+            ConfigUpdate::CurveParams(params) => {
+                params.validate()?;
+                let previous = self.curve_params();
+                self.init_bonding_curve = params.init_bonding_curve;
+                self.curve_limit = params.curve_limit;
+                self.complete_on_curve_limit = params.complete_on_curve_limit;
+                self.allowed_curve_kinds = params.allowed_curve_kinds;
+                self.initial_virtual_token_reserves_config = params.initial_virtual_token_reserves_config;
+                self.initial_virtual_sol_reserves_config = params.initial_virtual_sol_reserves_config;
+                self.initial_real_token_reserves_config = params.initial_real_token_reserves_config;
+                self.initial_meteora_token_reserves = params.initial_meteora_token_reserves;
+                self.initial_meteora_sol_amount = params.initial_meteora_sol_amount;
+                self.team_vesting_cliff = params.team_vesting_cliff;
+                self.team_vesting_duration = params.team_vesting_duration;
+                ConfigUpdate::CurveParams(previous)
+            }
+            ConfigUpdate::LaunchLimits(limits) => {
+                limits.validate()?;
+                let previous = self.launch_limits();
+                self.lamport_amount_config = limits.lamport_amount_config;
+                self.token_supply_config = limits.token_supply_config;
+                self.token_decimals_config = limits.token_decimals_config;
+                ConfigUpdate::LaunchLimits(previous)
+            }
+            ConfigUpdate::WhitelistEnabled(enabled) => {
+                let previous = self.whitelist_enabled;
+                self.whitelist_enabled = enabled;
+                ConfigUpdate::WhitelistEnabled(previous)
+            }
+            ConfigUpdate::ConfigChangeDelay(delay) => {
+                require!(delay >= 0, ContractError::InvalidConfigChangeDelay);
+                let previous = self.config_change_delay;
+                self.config_change_delay = delay;
+                ConfigUpdate::ConfigChangeDelay(previous)
+            }
+        };
+
+        Ok(previous)
+    }
+}
+
+//  one group of `Config` settings, as changed by the `set_*` admin instructions
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, PartialEq, Debug)]
+pub enum ConfigUpdate {
+    Fees(FeeParams),
+    Wallets(WalletParams),
+    CurveParams(CurveParams),
+    LaunchLimits(LaunchLimits),
+    ConfigChangeDelay(i64),
+    WhitelistEnabled(bool),
+}
+
+impl ConfigUpdate {
+    //  groups that can only change through `queue_config_change` once the delay has passed
+    pub fn is_timelocked(&self) -> bool {
+        matches!(
+            self,
+            Self::Fees(_) | Self::Wallets(_) | Self::ConfigChangeDelay(_)
+        )
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FeeParams {
+    pub platform_buy_fee_bps: u16,
+    pub platform_sell_fee_bps: u16,
+    pub platform_migration_fee_bps: u16,
+    pub creator_fee_bps: u16,
+    pub referral_share_bps: u16,
+}
+
+impl FeeParams {
+    pub fn validate(&self) -> Result<()> {
+        //  the creator fee is charged together with the buy and sell fees
+        for fee_bps in [
+            self.platform_buy_fee_bps as u64 + self.creator_fee_bps as u64,
+            self.platform_sell_fee_bps as u64 + self.creator_fee_bps as u64,
+            self.platform_migration_fee_bps as u64,
+            self.referral_share_bps as u64,
+        ] {
+            require!(fee_bps <= BPS_DENOMINATOR, ContractError::FeeTooHigh);
+        }
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct WalletParams {
+    pub team_wallet: Pubkey,
+    pub migration_wallet: Pubkey,
+}
+
+impl WalletParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.team_wallet != Pubkey::default() && self.migration_wallet != Pubkey::default(),
+            ContractError::InvalidWallet
+        );
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Debug)]
+pub struct CurveParams {
+    pub init_bonding_curve: f64,
+    pub curve_limit: u64,
+    pub complete_on_curve_limit: bool,
+    pub allowed_curve_kinds: u8,
+    pub initial_virtual_token_reserves_config: u64,
+    pub initial_virtual_sol_reserves_config: u64,
+    pub initial_real_token_reserves_config: u64,
+    pub initial_meteora_token_reserves: u64,
+    pub initial_meteora_sol_amount: u64,
+    pub team_vesting_cliff: i64,
+    pub team_vesting_duration: i64,
+}
+
+impl CurveParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.init_bonding_curve > 0.0 && self.init_bonding_curve <= 100.0,
+            ContractError::InvalidInitBondingCurve
+        );
+        require!(
+            self.allowed_curve_kinds != 0 && self.allowed_curve_kinds & !ALL_CURVE_KINDS == 0,
+            ContractError::InvalidAllowedCurveKinds
+        );
+        //  the curve can never hand out more tokens than its virtual reserves price
+        require!(
+            self.initial_virtual_sol_reserves_config > 0
+                && self.initial_real_token_reserves_config > 0
+                && self.initial_real_token_reserves_config
+                    < self.initial_virtual_token_reserves_config,
+            ContractError::InvalidReserves
+        );
+        require!(
+            !self.complete_on_curve_limit || self.curve_limit > 0,
+            ContractError::InvalidCurveLimit
+        );
+        require!(
+            self.team_vesting_cliff >= 0 && self.team_vesting_cliff <= self.team_vesting_duration,
+            ContractError::InvalidVestingSchedule
+        );
+        Ok(())
+    }
+
+    //  share of `token_supply` minted to the curve, the rest goes to the team vesting escrow.
+    //  `init_bonding_curve` is read to 0.01% so large supplies don't go through an f64.
+    pub fn curve_tokens(&self, token_supply: u128) -> u128 {
+        let share_bps = (self.init_bonding_curve * 100.0).round() as u128;
+        token_supply * share_bps / 10_000
+    }
+
+    //  rest of `token_supply`, minted into the team vesting escrow when there is any
+    pub fn team_tokens(&self, token_supply: u128) -> u128 {
+        token_supply - self.curve_tokens(token_supply)
+    }
+
+    //  lamports a curve of `curve_kind` holds once it completes, rounded down. constant product
+    //  curves sell out along the config's virtual reserves, the other kinds price the
+    //  `real_token_reserves` they launch with themselves.
+    pub fn completed_curve_sol(
+        &self,
+        curve_kind: &CurveKind,
+        real_token_reserves: u64,
+        decimals: u8,
+    ) -> Option<u64> {
+        let sold_out = match curve_kind {
+            CurveKind::ConstantProduct => {
+                let virtual_sol = self.initial_virtual_sol_reserves_config as u128;
+                let virtual_token = self.initial_virtual_token_reserves_config as u128;
+                let real_token = self.initial_real_token_reserves_config as u128;
+
+                //  selling every real token moves the virtual sol to virtual_sol * virtual_token / (virtual_token - real_token)
+                let sold_out = virtual_sol
+                    .checked_mul(real_token)?
+                    .checked_div(virtual_token.checked_sub(real_token)?)?;
+                sold_out.try_into().ok()?
+            }
+            _ => curve_kind.cost(0, real_token_reserves, decimals, Rounding::Down)?,
+        };
+
+        Some(match self.complete_on_curve_limit {
+            true => sold_out.min(self.curve_limit),
+            false => sold_out,
+        })
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, PartialEq, Debug)]
+pub struct LaunchLimits {
+    pub lamport_amount_config: AmountConfig<u64>,
+    pub token_supply_config: AmountConfig<u64>,
+    pub token_decimals_config: AmountConfig<u8>,
+}
+
+impl LaunchLimits {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.lamport_amount_config.is_satisfiable()
+                && self.token_supply_config.is_satisfiable()
+                && self.token_decimals_config.is_satisfiable(),
+            ContractError::InvalidLaunchLimits
+        );
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AuthorityRole {
+    Authority,
+    MigrationAuthority,
+}
+
+//  unversioned layout with f64 percentage fees, only read by `migrate_config`
+#[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+pub struct LegacyConfig {
+    pub authority: Pubkey,
+    pub migration_authority: Pubkey,
+
     pub team_wallet: Pubkey,
     pub migration_wallet: Pubkey,
 
-    pub init_bonding_curve: f64, // bonding curve init percentage. The remaining amount is sent to team wallet for distribution to agent
+    pub init_bonding_curve: f64,
 
-    pub platform_buy_fee: f64, //  platform fee percentage
+    pub platform_buy_fee: f64,
     pub platform_sell_fee: f64,
     pub platform_migration_fee: f64,
 
-    pub curve_limit: u64, //  lamports to complete te bonding curve
+    pub curve_limit: u64,
 
     pub lamport_amount_config: AmountConfig<u64>,
     pub token_supply_config: AmountConfig<u64>,
@@ -33,13 +551,93 @@ pub struct Config {
     pub initialized: bool,
 }
 
+impl LegacyConfig {
+    pub fn into_config(self) -> Result<Config> {
+        let to_bps = |fee: f64| bps_from_percentage(fee).ok_or(ContractError::ValueInvalid);
+
+        Ok(Config {
+            version: CONFIG_VERSION,
+            authority: self.authority,
+            migration_authority: self.migration_authority,
+            pending_authority: Pubkey::default(),
+            pending_migration_authority: Pubkey::default(),
+            team_wallet: self.team_wallet,
+            migration_wallet: self.migration_wallet,
+            init_bonding_curve: self.init_bonding_curve,
+            platform_buy_fee_bps: to_bps(self.platform_buy_fee)?,
+            platform_sell_fee_bps: to_bps(self.platform_sell_fee)?,
+            platform_migration_fee_bps: to_bps(self.platform_migration_fee)?,
+            curve_limit: self.curve_limit,
+            complete_on_curve_limit: false,
+            allowed_curve_kinds: CurveKind::ConstantProduct.mask(),
+            paused: 0,
+            config_change_delay: 0,
+            lamport_amount_config: self.lamport_amount_config,
+            token_supply_config: self.token_supply_config,
+            token_decimals_config: self.token_decimals_config,
+            initial_virtual_token_reserves_config: self.initial_virtual_token_reserves_config,
+            initial_virtual_sol_reserves_config: self.initial_virtual_sol_reserves_config,
+            initial_real_token_reserves_config: self.initial_real_token_reserves_config,
+            initial_meteora_token_reserves: self.initial_meteora_token_reserves,
+            initial_meteora_sol_amount: self.initial_meteora_sol_amount,
+            initialized: self.initialized,
+            whitelist_enabled: false,
+            team_vesting_cliff: 0,
+            team_vesting_duration: 0,
+            creator_fee_bps: 0,
+            referral_share_bps: 0,
+            reserved: [0; CONFIG_RESERVED],
+        })
+    }
+}
+
+//  longest option list an `AmountConfig::Enum` may hold, so `Config` has a fixed size
+pub const MAX_AMOUNT_OPTIONS: usize = 8;
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
 pub enum AmountConfig<T: PartialEq + PartialOrd + Debug> {
     Range { min: Option<T>, max: Option<T> },
     Enum(Vec<T>),
 }
 
+//  `InitSpace` can't be derived for generic enums, `value_space` is the encoded size of `T`
+const fn amount_config_space(value_space: usize) -> usize {
+    let range = 2 * (1 + value_space);
+    let options = 4 + MAX_AMOUNT_OPTIONS * value_space;
+    1 + if range > options { range } else { options }
+}
+
+impl Space for AmountConfig<u64> {
+    const INIT_SPACE: usize = amount_config_space(8);
+}
+
+impl Space for AmountConfig<u8> {
+    const INIT_SPACE: usize = amount_config_space(1);
+}
+
 impl<T: PartialEq + PartialOrd + Debug> AmountConfig<T> {
+    //  false when no value could ever pass `validate`, or the options don't fit the account
+    pub fn is_satisfiable(&self) -> bool {
+        match self {
+            Self::Range {
+                min: Some(min),
+                max: Some(max),
+            } => min <= max,
+            Self::Range { .. } => true,
+            Self::Enum(options) => !options.is_empty() && options.len() <= MAX_AMOUNT_OPTIONS,
+        }
+    }
+
+    //  largest value `validate` accepts, `None` when unbounded
+    pub fn largest(&self) -> Option<&T> {
+        match self {
+            Self::Range { max, .. } => max.as_ref(),
+            Self::Enum(options) => options
+                .iter()
+                .reduce(|largest, option| if option > largest { option } else { largest }),
+        }
+    }
+
     pub fn validate(&self, value: &T) -> Result<()> {
         match self {
             Self::Range { min, max } => {
@@ -69,3 +667,483 @@ impl<T: PartialEq + PartialOrd + Debug> AmountConfig<T> {
         }
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+
+    pub(crate) fn config_with_fees(platform_buy_fee_bps: u16, platform_sell_fee_bps: u16) -> Config {
+        Config {
+            version: CONFIG_VERSION,
+            authority: Pubkey::default(),
+            migration_authority: Pubkey::default(),
+            pending_authority: Pubkey::default(),
+            pending_migration_authority: Pubkey::default(),
+            team_wallet: Pubkey::default(),
+            migration_wallet: Pubkey::default(),
+            init_bonding_curve: 100.0,
+            platform_buy_fee_bps,
+            platform_sell_fee_bps,
+            platform_migration_fee_bps: 0,
+            curve_limit: 0,
+            complete_on_curve_limit: false,
+            allowed_curve_kinds: CurveKind::ConstantProduct.mask(),
+            paused: 0,
+            config_change_delay: 0,
+            lamport_amount_config: AmountConfig::Range { min: None, max: None },
+            token_supply_config: AmountConfig::Range { min: None, max: None },
+            token_decimals_config: AmountConfig::Range { min: None, max: None },
+            initial_virtual_token_reserves_config: 0,
+            initial_virtual_sol_reserves_config: 0,
+            initial_real_token_reserves_config: 0,
+            initial_meteora_token_reserves: 0,
+            initial_meteora_sol_amount: 0,
+            initialized: true,
+            whitelist_enabled: false,
+            team_vesting_cliff: 0,
+            team_vesting_duration: 0,
+            creator_fee_bps: 0,
+            referral_share_bps: 0,
+            reserved: [0; CONFIG_RESERVED],
+        }
+    }
+
+    #[test]
+    fn creator_fee_is_charged_on_top_of_the_platform_fee() {
+        let mut config = config_with_fees(100, 250);
+        config.creator_fee_bps = 50;
+
+        let buy = config.buy_fee(1_000_000_000).unwrap();
+        assert_eq!(buy.fee, 15_000_000);
+        assert_eq!(config.creator_fee(buy.fee, config.buy_fee_bps()), Some(5_000_000));
+
+        let sell = config.sell_fee(1_000_000_000).unwrap();
+        assert_eq!(sell.fee, 30_000_000);
+        assert_eq!(config.creator_fee(sell.fee, config.sell_fee_bps()), Some(5_000_000));
+
+        //  the creator's part is rounded down, the platform keeps the dust
+        assert_eq!(config.creator_fee(2, config.buy_fee_bps()), Some(0));
+
+        //  referrers get their slice of what the platform keeps
+        config.referral_share_bps = 2_000;
+        assert_eq!(config.referral_fee(buy.fee - 5_000_000), Some(2_000_000));
+        assert_eq!(config.referral_fee(4), Some(0));
+    }
+
+    #[test]
+    fn buy_and_sell_fees_are_independent() {
+        let config = config_with_fees(100, 250);
+
+        assert_eq!(config.buy_fee(1_000_000_000).unwrap().fee, 10_000_000);
+        assert_eq!(config.sell_fee(1_000_000_000).unwrap().fee, 25_000_000);
+
+        let config = config_with_fees(300, 0);
+
+        assert_eq!(config.buy_fee(1_000_000_000).unwrap().fee, 30_000_000);
+        assert_eq!(config.sell_fee(1_000_000_000).unwrap().fee, 0);
+        assert_eq!(config.sell_fee(1_000_000_000).unwrap().net, 1_000_000_000);
+    }
+
+    #[test]
+    fn authority_moves_only_once_the_proposed_key_accepts() {
+        let mut config = config_with_fees(0, 0);
+        let (admin, next, stranger) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        config.authority = admin;
+
+        assert!(config.accept_authority(AuthorityRole::Authority, next).is_err());
+        assert!(config.propose_authority(AuthorityRole::Authority, Pubkey::default()).is_err());
+
+        config.propose_authority(AuthorityRole::Authority, next).unwrap();
+        assert_eq!(config.authority, admin);
+        assert!(config.accept_authority(AuthorityRole::Authority, stranger).is_err());
+        assert!(config.accept_authority(AuthorityRole::MigrationAuthority, next).is_err());
+
+        assert_eq!(config.accept_authority(AuthorityRole::Authority, next).unwrap(), admin);
+        assert_eq!(config.authority, next);
+        assert_eq!(config.pending_authority, Pubkey::default());
+        assert_eq!(config.migration_authority, Pubkey::default());
+    }
+
+    #[test]
+    fn cancelled_proposals_can_not_be_accepted() {
+        let mut config = config_with_fees(0, 0);
+        let next = Pubkey::new_unique();
+
+        assert!(config.cancel_authority_transfer(AuthorityRole::MigrationAuthority).is_err());
+
+        config.propose_authority(AuthorityRole::MigrationAuthority, next).unwrap();
+        assert_eq!(
+            config.cancel_authority_transfer(AuthorityRole::MigrationAuthority).unwrap(),
+            next
+        );
+        assert!(config.accept_authority(AuthorityRole::MigrationAuthority, next).is_err());
+        assert_eq!(config.migration_authority, Pubkey::default());
+    }
+
+    #[test]
+    fn updates_return_the_previous_values_and_leave_other_groups_alone() {
+        let mut config = config_with_fees(100, 250);
+        let wallets = config.wallet_params();
+
+        let previous = config
+            .apply_update(ConfigUpdate::Fees(FeeParams {
+                platform_buy_fee_bps: 50,
+                platform_sell_fee_bps: 60,
+                platform_migration_fee_bps: 70,
+                creator_fee_bps: 30,
+                referral_share_bps: 2_000,
+            }))
+            .unwrap();
+
+        assert_eq!(
+            previous,
+            ConfigUpdate::Fees(FeeParams {
+                platform_buy_fee_bps: 100,
+                platform_sell_fee_bps: 250,
+                platform_migration_fee_bps: 0,
+                creator_fee_bps: 0,
+                referral_share_bps: 0,
+            })
+        );
+        assert_eq!(config.platform_migration_fee_bps, 70);
+        assert_eq!(config.creator_fee_bps, 30);
+        assert_eq!(config.referral_share_bps, 2_000);
+        assert_eq!(config.wallet_params(), wallets);
+        assert!(config.initialized);
+    }
+
+    #[test]
+    fn invalid_updates_are_rejected_untouched() {
+        let mut config = config_with_fees(100, 250);
+
+        let mut fees = config.fee_params();
+        fees.platform_sell_fee_bps = 10_001;
+        assert!(config.apply_update(ConfigUpdate::Fees(fees)).is_err());
+        assert_eq!(config.platform_sell_fee_bps, 250);
+
+        //  250 + 9_800 is more than the whole sell
+        fees.platform_sell_fee_bps = 250;
+        fees.creator_fee_bps = 9_800;
+        assert!(config.apply_update(ConfigUpdate::Fees(fees)).is_err());
+        assert_eq!(config.creator_fee_bps, 0);
+
+        fees.creator_fee_bps = 0;
+        fees.referral_share_bps = 10_001;
+        assert!(config.apply_update(ConfigUpdate::Fees(fees)).is_err());
+
+        let wallets = WalletParams {
+            team_wallet: Pubkey::new_unique(),
+            migration_wallet: Pubkey::default(),
+        };
+        assert!(config.apply_update(ConfigUpdate::Wallets(wallets)).is_err());
+
+        let mut params = CurveParams {
+            init_bonding_curve: 80.0,
+            curve_limit: 0,
+            complete_on_curve_limit: false,
+            allowed_curve_kinds: 1,
+            initial_virtual_token_reserves_config: 1_073_000_000_000_000,
+            initial_virtual_sol_reserves_config: 30_000_000_000,
+            initial_real_token_reserves_config: 793_100_000_000_000,
+            initial_meteora_token_reserves: 206_900_000_000_000,
+            initial_meteora_sol_amount: 40_000_000_000,
+            team_vesting_cliff: 0,
+            team_vesting_duration: 0,
+        };
+        assert!(params.validate().is_ok());
+        params.team_vesting_cliff = 1;
+        assert!(params.validate().is_err());
+        params.team_vesting_cliff = 0;
+        params.complete_on_curve_limit = true;
+        assert!(params.validate().is_err());
+        params.curve_limit = 85_000_000_000;
+        params.allowed_curve_kinds = 1 << 4;
+        assert!(params.validate().is_err());
+        params.allowed_curve_kinds = 1;
+        params.initial_real_token_reserves_config = params.initial_virtual_token_reserves_config;
+        assert!(config.apply_update(ConfigUpdate::CurveParams(params)).is_err());
+
+        let limits = LaunchLimits {
+            lamport_amount_config: AmountConfig::Range { min: Some(2), max: Some(1) },
+            token_supply_config: AmountConfig::Range { min: None, max: None },
+            token_decimals_config: AmountConfig::Enum(vec![6]),
+        };
+        assert!(config.apply_update(ConfigUpdate::LaunchLimits(limits)).is_err());
+        assert_eq!(config.launch_limits().token_decimals_config, AmountConfig::Range { min: None, max: None });
+    }
+
+    fn valid_config() -> Config {
+        let mut config = config_with_fees(100, 100);
+        config.team_wallet = Pubkey::new_unique();
+        config.migration_wallet = Pubkey::new_unique();
+        config.platform_migration_fee_bps = 100;
+        config.token_supply_config = AmountConfig::Enum(vec![1_000_000_000]);
+        config.initial_virtual_token_reserves_config = 1_073_000_000_000_000;
+        config.initial_virtual_sol_reserves_config = 30_000_000_000;
+        config.initial_real_token_reserves_config = 793_100_000_000_000;
+        config.initial_meteora_token_reserves = 206_900_000_000_000;
+        config.initial_meteora_sol_amount = 40_000_000_000;
+        config
+    }
+
+    #[test]
+    fn inconsistent_configs_are_rejected_with_specific_errors() {
+        assert!(valid_config().validate().is_ok());
+
+        let assert_rejects = |config: Config, error: ContractError| {
+            assert_eq!(config.validate().unwrap_err(), error.into());
+        };
+
+        let mut config = valid_config();
+        config.platform_buy_fee_bps = 10_001;
+        assert_rejects(config, ContractError::FeeTooHigh);
+
+        let mut config = valid_config();
+        config.token_supply_config = AmountConfig::Range { min: None, max: Some(999_999_999) };
+        assert_rejects(config, ContractError::ReservesExceedSupply);
+
+        //  unbounded supplies always leave room for the reserves
+        let mut config = valid_config();
+        config.token_supply_config = AmountConfig::Range { min: Some(1), max: None };
+        assert!(config.validate().is_ok());
+
+        //  selling out raises ~85 SOL, the migration fee and pool reserve leave ~84.1
+        let mut config = valid_config();
+        config.initial_meteora_sol_amount = 85_000_000_000;
+        assert_rejects(config, ContractError::MeteoraSolExceedsRaise);
+
+        let mut config = valid_config();
+        config.complete_on_curve_limit = true;
+        config.curve_limit = 40_000_000_000;
+        assert_rejects(config, ContractError::MeteoraSolExceedsRaise);
+
+        let mut config = valid_config();
+        config.initial_real_token_reserves_config = config.initial_virtual_token_reserves_config;
+        assert_rejects(config, ContractError::InvalidReserves);
+
+        let mut config = valid_config();
+        config.token_decimals_config = AmountConfig::Enum(vec![]);
+        assert_rejects(config, ContractError::InvalidLaunchLimits);
+    }
+
+    fn serialized(config: &Config) -> Vec<u8> {
+        let mut data = Vec::new();
+        config.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn longest_option_lists_fill_the_config_space_exactly() {
+        let mut config = valid_config();
+        config.lamport_amount_config = AmountConfig::Enum(vec![u64::MAX; MAX_AMOUNT_OPTIONS]);
+        config.token_supply_config = AmountConfig::Enum(vec![1_000_000_000; MAX_AMOUNT_OPTIONS]);
+        config.token_decimals_config = AmountConfig::Enum(vec![6; MAX_AMOUNT_OPTIONS]);
+        assert!(config.validate().is_ok());
+        assert_eq!(serialized(&config).len(), 8 + Config::INIT_SPACE);
+
+        //  ranges encode shorter than full option lists
+        assert!(serialized(&valid_config()).len() < 8 + Config::INIT_SPACE);
+
+        config.token_decimals_config = AmountConfig::Enum(vec![6; MAX_AMOUNT_OPTIONS + 1]);
+        assert_eq!(config.validate().unwrap_err(), ContractError::InvalidLaunchLimits.into());
+    }
+
+    #[test]
+    fn fields_carved_from_reserved_read_as_zero_on_older_configs() {
+        let mut config = valid_config();
+        config.whitelist_enabled = true;
+        config.team_vesting_cliff = 86_400;
+        config.team_vesting_duration = 30 * 86_400;
+        config.creator_fee_bps = 50;
+        config.referral_share_bps = 2_000;
+        let mut data = serialized(&config);
+        assert_eq!(Config::current(&data).unwrap().team_vesting_cliff, 86_400);
+
+        //  whitelist_enabled, team_vesting_cliff, team_vesting_duration, creator_fee_bps
+        //  and referral_share_bps
+        let carved = 1 + 8 + 8 + 2 + 2;
+        let len = data.len();
+        data[len - CONFIG_RESERVED - carved..].fill(0);
+        let older = Config::current(&data).unwrap();
+        assert!(!older.whitelist_enabled);
+        assert_eq!(older.team_vesting_cliff, 0);
+        assert_eq!(older.team_vesting_duration, 0);
+        assert_eq!(older.creator_fee_bps, 0);
+        assert_eq!(older.referral_share_bps, 0);
+        assert!(older.validate().is_ok());
+    }
+
+    #[test]
+    fn every_curve_kind_has_to_raise_the_meteora_sol() {
+        use crate::state::pricing::{PriceTier, MAX_PRICE_TIERS, PRICE_SCALE};
+
+        //  every kind below prices the curve's whole tokens at a flat `lamports` each
+        let price = |lamports: u64| lamports * PRICE_SCALE as u64;
+        let stepwise = |lamports: u64| {
+            let mut tiers = [PriceTier::default(); MAX_PRICE_TIERS];
+            tiers[0] = PriceTier { end: 793_100_000, price: price(lamports) };
+            CurveKind::Stepwise { tiers, tier_count: 1 }
+        };
+        let exponential = |lamports: u64| CurveKind::Exponential {
+            start_price: price(lamports),
+            growth_bps: 1,
+            step_size: u64::MAX,
+        };
+        //  (kind selling out for ~0.8 SOL, kind selling out for ~47.6 SOL)
+        let kinds = [
+            (
+                CurveKind::Linear { start_price: price(1), slope: 0 },
+                CurveKind::Linear { start_price: price(60), slope: 0 },
+            ),
+            (exponential(1), exponential(60)),
+            (stepwise(1), stepwise(60)),
+        ];
+
+        //  the same check `create_bonding_curve` runs on the launch's own reserves
+        let launch = |config: &Config, curve_kind: &CurveKind| {
+            let completed_sol = config
+                .curve_params()
+                .completed_curve_sol(curve_kind, 793_100_000_000_000, CONFIG_TOKEN_DECIMALS)
+                .unwrap();
+            config.require_raise_funds_pool(completed_sol)
+        };
+
+        //  constant product raises don't constrain configs that don't allow the kind
+        let mut config = valid_config();
+        config.allowed_curve_kinds = ALL_CURVE_KINDS & !CurveKind::ConstantProduct.mask();
+        config.initial_meteora_sol_amount = 45_000_000_000;
+        assert!(config.validate().is_ok());
+
+        for (cheap, priced) in kinds {
+            assert!(cheap.validate().is_ok() && priced.validate().is_ok());
+            assert_eq!(
+                launch(&config, &cheap).unwrap_err(),
+                ContractError::MeteoraSolExceedsRaise.into()
+            );
+            assert!(launch(&config, &priced).is_ok());
+
+            //  completing early at the curve limit caps the raise
+            let mut limited = config.clone();
+            limited.complete_on_curve_limit = true;
+            limited.curve_limit = 45_000_000_000;
+            assert_eq!(
+                launch(&limited, &priced).unwrap_err(),
+                ContractError::MeteoraSolExceedsRaise.into()
+            );
+        }
+
+        let mut config = valid_config();
+        assert!(launch(&config, &CurveKind::ConstantProduct).is_ok());
+        config.initial_meteora_sol_amount = 85_000_000_000;
+        assert!(launch(&config, &CurveKind::ConstantProduct).is_err());
+    }
+
+    #[test]
+    fn only_the_curve_share_funds_the_curve_and_pool() {
+        let mut config = valid_config();
+        assert_eq!(config.curve_params().curve_tokens(1_000), 1_000);
+        //  launches that don't need a vesting escrow
+        for token_supply in [0, 1, 999, 1_000_000_000_000_000, u64::MAX as u128] {
+            assert_eq!(config.curve_params().team_tokens(token_supply), 0);
+        }
+
+        config.init_bonding_curve = 80.0;
+        assert_eq!(config.curve_params().curve_tokens(1_000_000), 800_000);
+        assert_eq!(config.curve_params().team_tokens(1_000_000), 200_000);
+        assert_eq!(config.curve_params().team_tokens(1), 1);
+        assert_eq!(
+            config.validate().unwrap_err(),
+            ContractError::ReservesExceedSupply.into()
+        );
+
+        config.initial_real_token_reserves_config = 593_100_000_000_000;
+        config.initial_meteora_sol_amount = 30_000_000_000;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn only_current_layouts_are_read_as_current() {
+        let config = valid_config();
+        assert_eq!(Config::current(&serialized(&config)).unwrap().team_wallet, config.team_wallet);
+
+        let mut outdated = valid_config();
+        outdated.version = CONFIG_VERSION - 1;
+        assert!(Config::current(&serialized(&outdated)).is_none());
+
+        //  the unversioned legacy layout upgrades into the current one
+        let legacy = LegacyConfig {
+            authority: Pubkey::new_unique(),
+            migration_authority: Pubkey::new_unique(),
+            team_wallet: config.team_wallet,
+            migration_wallet: config.migration_wallet,
+            init_bonding_curve: 80.0,
+            platform_buy_fee: 0.69,
+            platform_sell_fee: 0.69,
+            platform_migration_fee: 1.0,
+            curve_limit: 42_000_000_000,
+            lamport_amount_config: config.lamport_amount_config.clone(),
+            token_supply_config: config.token_supply_config.clone(),
+            token_decimals_config: config.token_decimals_config.clone(),
+            initial_virtual_token_reserves_config: config.initial_virtual_token_reserves_config,
+            initial_virtual_sol_reserves_config: config.initial_virtual_sol_reserves_config,
+            initial_real_token_reserves_config: config.initial_real_token_reserves_config,
+            initial_meteora_token_reserves: config.initial_meteora_token_reserves,
+            initial_meteora_sol_amount: config.initial_meteora_sol_amount,
+            initialized: true,
+        };
+        let legacy_data = [&Config::DISCRIMINATOR[..], &legacy.try_to_vec().unwrap()].concat();
+        assert!(Config::current(&legacy_data).is_none());
+
+        let upgraded = LegacyConfig::deserialize(&mut &legacy_data[8..])
+            .unwrap()
+            .into_config()
+            .unwrap();
+        assert_eq!(upgraded.version, CONFIG_VERSION);
+        assert_eq!(upgraded.platform_buy_fee_bps, 69);
+        assert!(Config::current(&serialized(&upgraded)).is_some());
+    }
+
+    #[test]
+    fn fee_wallet_and_delay_changes_are_timelocked() {
+        let mut config = valid_config();
+
+        assert!(ConfigUpdate::Fees(config.fee_params()).is_timelocked());
+        assert!(ConfigUpdate::Wallets(config.wallet_params()).is_timelocked());
+        assert!(ConfigUpdate::ConfigChangeDelay(0).is_timelocked());
+        assert!(!ConfigUpdate::CurveParams(config.curve_params()).is_timelocked());
+        assert!(!ConfigUpdate::LaunchLimits(config.launch_limits()).is_timelocked());
+        assert!(!ConfigUpdate::WhitelistEnabled(true).is_timelocked());
+
+        assert_eq!(
+            config.apply_update(ConfigUpdate::ConfigChangeDelay(86_400)).unwrap(),
+            ConfigUpdate::ConfigChangeDelay(0)
+        );
+        assert!(config.apply_update(ConfigUpdate::ConfigChangeDelay(-1)).is_err());
+        assert_eq!(config.config_change_delay, 86_400);
+    }
+
+    #[test]
+    fn pause_flags_gate_each_action_separately() {
+        let mut config = valid_config();
+        config.paused = PAUSE_BUY | PAUSE_MIGRATE;
+
+        assert!(config.require_not_paused(PAUSE_CREATE).is_ok());
+        assert!(config.require_not_paused(PAUSE_SELL).is_ok());
+        assert_eq!(config.require_not_paused(PAUSE_BUY).unwrap_err(), ContractError::Paused.into());
+        assert!(config.require_not_paused(PAUSE_MIGRATE).is_err());
+        assert!(config.validate().is_ok());
+
+        config.paused = 1 << 4;
+        assert_eq!(config.validate().unwrap_err(), ContractError::InvalidPauseFlags.into());
+    }
+
+    #[test]
+    fn zero_buy_fee_passes_full_amount_to_curve() {
+        let config = config_with_fees(0, 9_000);
+        let split = config.buy_fee(123_456_789).unwrap();
+
+        assert_eq!(split.fee, 0);
+        assert_eq!(split.net, 123_456_789);
+    }
+}
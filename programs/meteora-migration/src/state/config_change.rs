@@ -0,0 +1,66 @@
+use crate::state::config::ConfigUpdate;
+use anchor_lang::prelude::*;
+
+//  a timelocked `ConfigUpdate` waiting for `execute_config_change`. there is at most one,
+//  it has to be executed or cancelled before the next change is queued.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct PendingConfigChange {
+    pub change: ConfigUpdate,
+    pub queued_at: i64,
+    pub executable_at: i64,
+}
+
+impl PendingConfigChange {
+    pub fn new(change: ConfigUpdate, now: i64, delay: i64) -> Option<Self> {
+        Some(Self {
+            change,
+            queued_at: now,
+            executable_at: now.checked_add(delay)?,
+        })
+    }
+
+    pub fn is_executable(&self, now: i64) -> bool {
+        now >= self.executable_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::config::{FeeParams, WalletParams};
+
+    #[test]
+    fn changes_execute_only_after_the_delay() {
+        let fees = FeeParams {
+            platform_buy_fee_bps: 100,
+            platform_sell_fee_bps: 100,
+            platform_migration_fee_bps: 100,
+            creator_fee_bps: 0,
+            referral_share_bps: 0,
+        };
+        let pending = PendingConfigChange::new(ConfigUpdate::Fees(fees), 1_700_000_000, 3_600).unwrap();
+
+        assert!(!pending.is_executable(1_700_000_000));
+        assert!(!pending.is_executable(1_700_003_599));
+        assert!(pending.is_executable(1_700_003_600));
+
+        let immediate = PendingConfigChange::new(ConfigUpdate::ConfigChangeDelay(0), 1_700_000_000, 0).unwrap();
+        assert!(immediate.is_executable(1_700_000_000));
+
+        assert!(PendingConfigChange::new(ConfigUpdate::ConfigChangeDelay(0), i64::MAX, 1).is_none());
+    }
+
+    #[test]
+    fn largest_timelocked_change_fits_the_account() {
+        let wallets = WalletParams {
+            team_wallet: Pubkey::new_unique(),
+            migration_wallet: Pubkey::new_unique(),
+        };
+        let pending = PendingConfigChange::new(ConfigUpdate::Wallets(wallets), 0, 0).unwrap();
+
+        let mut data = Vec::new();
+        pending.try_serialize(&mut data).unwrap();
+        assert!(data.len() <= 8 + PendingConfigChange::INIT_SPACE);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::errors::*;
+use anchor_lang::prelude::*;
+
+//  when a curve opens for trading, picked by the creator at launch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LaunchSchedule {
+    //  0 or a time in the past opens the curve right away
+    pub start_time: i64,
+    //  until then only wallets with a `PresaleAllowance` may buy, 0 skips the presale
+    pub presale_end_time: i64,
+}
+
+impl LaunchSchedule {
+    //  pins a past start time to `now`, so the stored schedule never starts before the launch
+    pub fn resolve(self, now: i64) -> Result<LaunchSchedule> {
+        let start_time = self.start_time.max(now);
+        require!(
+            self.presale_end_time == 0 || self.presale_end_time > start_time,
+            ContractError::InvalidLaunchSchedule
+        );
+
+        Ok(LaunchSchedule {
+            start_time,
+            presale_end_time: self.presale_end_time,
+        })
+    }
+
+    pub fn phase(&self, now: i64) -> LaunchPhase {
+        if now < self.start_time {
+            LaunchPhase::Scheduled
+        } else if now < self.presale_end_time {
+            LaunchPhase::Presale
+        } else {
+            LaunchPhase::Public
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LaunchPhase {
+    //  no trades before `start_time`
+    Scheduled,
+    //  buys limited to allowlisted wallets, sells are open
+    Presale,
+    Public,
+}
+
+//  buy made by the creator within `create_bonding_curve`, so no one can trade ahead of it.
+//  it is made even when the launch is scheduled or opens with a presale, up to `max_tokens_out`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct InitialBuy {
+    //  SOL to spend, fee included
+    pub lamports: u64,
+    //  when `lamports` would buy more, only this many tokens are bought, for less SOL
+    pub max_tokens_out: u64,
+}
+
+//  lets `wallet` buy up to `cap` lamports, fee included, of the curve of `token_mint`
+//  during its presale. managed by the curve's creator.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct PresaleAllowance {
+    pub token_mint: Pubkey,
+    pub wallet: Pubkey,
+    pub cap: u64,
+    pub spent: u64,
+}
+
+impl PresaleAllowance {
+    pub fn record_buy(&mut self, lamports: u64) -> Result<()> {
+        let spent = self
+            .spent
+            .checked_add(lamports)
+            .ok_or(ContractError::ArithmeticError)?;
+        require!(spent <= self.cap, ContractError::PresaleCapExceeded);
+
+        self.spent = spent;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phases_follow_the_clock() {
+        let schedule = LaunchSchedule {
+            start_time: 100,
+            presale_end_time: 200,
+        }
+        .resolve(50)
+        .unwrap();
+
+        assert_eq!(schedule.phase(99), LaunchPhase::Scheduled);
+        assert_eq!(schedule.phase(100), LaunchPhase::Presale);
+        assert_eq!(schedule.phase(199), LaunchPhase::Presale);
+        assert_eq!(schedule.phase(200), LaunchPhase::Public);
+
+        //  curves launched before schedules existed read as zero and are public
+        let unscheduled = LaunchSchedule {
+            start_time: 0,
+            presale_end_time: 0,
+        };
+        assert_eq!(unscheduled.phase(0), LaunchPhase::Public);
+
+        let past_start = LaunchSchedule {
+            start_time: 10,
+            presale_end_time: 0,
+        }
+        .resolve(50)
+        .unwrap();
+        assert_eq!(past_start.start_time, 50);
+        assert_eq!(past_start.phase(50), LaunchPhase::Public);
+
+        //  a presale has to end after trading starts
+        assert!(LaunchSchedule {
+            start_time: 100,
+            presale_end_time: 100,
+        }
+        .resolve(50)
+        .is_err());
+        assert!(LaunchSchedule {
+            start_time: 0,
+            presale_end_time: 40,
+        }
+        .resolve(50)
+        .is_err());
+    }
+
+    #[test]
+    fn presale_buys_stop_at_the_cap() {
+        let mut allowance = PresaleAllowance {
+            token_mint: Pubkey::new_unique(),
+            wallet: Pubkey::new_unique(),
+            cap: 1_000,
+            spent: 0,
+        };
+
+        allowance.record_buy(600).unwrap();
+        assert!(allowance.record_buy(401).is_err());
+        assert_eq!(allowance.spent, 600);
+        allowance.record_buy(400).unwrap();
+        assert_eq!(allowance.spent, 1_000);
+    }
+}
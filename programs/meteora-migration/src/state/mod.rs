@@ -1,3 +1,9 @@
 pub mod config;
+pub mod config_change;
+pub mod launch;
 pub mod bondingcurve;
-pub mod meteora;
\ No newline at end of file
+pub mod meteora;
+pub mod pricing;
+pub mod referral;
+pub mod vesting;
+pub mod whitelist;
\ No newline at end of file
@@ -0,0 +1,590 @@
+use crate::errors::*;
+use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
+
+//  prices are lamports per whole token, scaled up by `PRICE_SCALE`
+pub const PRICE_SCALE: u128 = 1_000_000_000;
+pub const MAX_PRICE_TIERS: usize = 8;
+//  every bit `CurveKind::mask` can return
+pub const ALL_CURVE_KINDS: u8 = 0b1111;
+
+const FIXED_POINT: u128 = 1_000_000_000_000_000_000;
+const BPS_DENOMINATOR: u128 = 10_000;
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct PriceTier {
+    pub end: u64,   //  whole tokens sold when this tier ends
+    pub price: u64, //  scaled by `PRICE_SCALE`
+}
+
+//  shape of a bonding curve. the constant product curve prices off the virtual reserves
+//  stored on `BondingCurve`, every other kind prices off the amount of tokens sold.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CurveKind {
+    ConstantProduct,
+    //  price grows by `slope` for every whole token sold
+    Linear {
+        start_price: u64,
+        slope: u64,
+    },
+    //  price grows by `growth_bps` every `step_size` whole tokens sold
+    Exponential {
+        start_price: u64,
+        growth_bps: u16,
+        step_size: u64,
+    },
+    //  fixed price per tier, the last tier's price holds once every tier is sold
+    Stepwise {
+        tiers: [PriceTier; MAX_PRICE_TIERS],
+        tier_count: u8,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+impl CurveKind {
+    //  bit of this kind in `Config.allowed_curve_kinds`
+    pub fn mask(&self) -> u8 {
+        match self {
+            Self::ConstantProduct => 1 << 0,
+            Self::Linear { .. } => 1 << 1,
+            Self::Exponential { .. } => 1 << 2,
+            Self::Stepwise { .. } => 1 << 3,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let valid = match self {
+            Self::ConstantProduct => true,
+            Self::Linear { start_price, .. } => *start_price > 0,
+            Self::Exponential {
+                start_price,
+                growth_bps,
+                step_size,
+            } => *start_price > 0 && *growth_bps > 0 && *step_size > 0,
+            Self::Stepwise { tiers, tier_count } => {
+                let tiers = tiers.get(..*tier_count as usize).unwrap_or_default();
+                !tiers.is_empty()
+                    && tiers.iter().all(|tier| tier.price > 0)
+                    && tiers.windows(2).all(|pair| pair[0].end < pair[1].end)
+            }
+        };
+
+        require!(valid, ContractError::InvalidCurveParams);
+        Ok(())
+    }
+
+    //  lamports paid for the tokens between `from` and `to` tokens sold
+    pub fn cost(&self, from: u64, to: u64, decimals: u8, rounding: Rounding) -> Option<u64> {
+        let unit = 10u128.checked_pow(decimals as u32)?;
+        let scaled = self.scaled_cost(from as u128, to as u128, unit, rounding)?;
+
+        mul_div(scaled, 1, unit.checked_mul(PRICE_SCALE)?, rounding)?
+            .try_into()
+            .ok()
+    }
+
+    //  most tokens `sol_amount` buys with `tokens_sold` already sold, capped at `token_reserves`
+    pub fn tokens_for_sol(
+        &self,
+        tokens_sold: u64,
+        token_reserves: u64,
+        sol_amount: u64,
+        decimals: u8,
+    ) -> Option<u64> {
+        let unit = 10u128.checked_pow(decimals as u32)?;
+        let from = tokens_sold as u128;
+        let to = from.checked_add(token_reserves as u128)?;
+        let budget = (sol_amount as u128)
+            .checked_mul(unit)?
+            .checked_mul(PRICE_SCALE)?;
+
+        //  ceil(scaled / (unit * PRICE_SCALE)) <= sol_amount  <=>  scaled <= budget
+        if self.scaled_cost(from, to, unit, Rounding::Up)? <= budget {
+            return Some(token_reserves);
+        }
+
+        let tokens_out = match self {
+            Self::ConstantProduct => return None,
+            Self::Linear { .. } => {
+                //  largest amount whose cost fits the budget
+                let (mut low, mut high) = (0u128, token_reserves as u128);
+                while low < high {
+                    let mid = low + (high - low).div_ceil(2);
+                    if self.scaled_cost(from, from + mid, unit, Rounding::Up)? <= budget {
+                        low = mid;
+                    } else {
+                        high = mid - 1;
+                    }
+                }
+                low
+            }
+            Self::Exponential {
+                start_price,
+                growth_bps,
+                step_size,
+            } => {
+                let step = (*step_size as u128).checked_mul(unit)?;
+                let growth = *growth_bps as u128;
+                let ratio = FIXED_POINT.checked_add(mul_div(
+                    FIXED_POINT,
+                    growth,
+                    BPS_DENOMINATOR,
+                    Rounding::Up,
+                )?)?;
+
+                //  rest of the current step
+                let first_step = from / step;
+                let first_price = step_price(*start_price, ratio, first_step, Rounding::Up)?;
+                let first_tokens = step.checked_mul(first_step + 1)?.checked_sub(from)?;
+                let first_cost = first_price.checked_mul(first_tokens)?;
+                if budget < first_cost {
+                    return budget.checked_div(first_price)?.try_into().ok();
+                }
+                let mut remaining = budget - first_cost;
+
+                //  as many whole steps as the budget covers, found by binary lifting over ratio^(2^j)
+                let base_power = pow_fixed(ratio, first_step + 1, Rounding::Down)?;
+                let mut lifts = vec![ratio];
+                while lifts.len() < 64 {
+                    let last = *lifts.last()?;
+                    match mul_div(last, last, FIXED_POINT, Rounding::Up) {
+                        Some(next) if next < FIXED_POINT.saturating_mul(1_000_000_000_000) => {
+                            lifts.push(next)
+                        }
+                        _ => break,
+                    }
+                }
+
+                let full_steps_cost = |power: u128| -> Option<u128> {
+                    let price_sum = mul_div(
+                        *start_price as u128,
+                        power.checked_sub(base_power)?,
+                        FIXED_POINT,
+                        Rounding::Up,
+                    )?;
+                    mul_div(price_sum, BPS_DENOMINATOR, growth, Rounding::Up)?.checked_mul(step)
+                };
+
+                let mut steps = 0u128;
+                let mut power = base_power;
+                for (index, lift) in lifts.iter().enumerate().rev() {
+                    let Some(candidate) = mul_div(power, *lift, FIXED_POINT, Rounding::Up) else {
+                        continue;
+                    };
+                    if matches!(full_steps_cost(candidate), Some(cost) if cost <= remaining) {
+                        power = candidate;
+                        steps += 1 << index;
+                    }
+                }
+                remaining = remaining.checked_sub(full_steps_cost(power)?)?;
+
+                //  part of the next step
+                let price = mul_div(*start_price as u128, power, FIXED_POINT, Rounding::Up)?;
+                let partial = remaining.checked_div(price)?.min(step - 1);
+
+                //  the lifted powers round differently from `scaled_cost`, trim any overshoot
+                let mut tokens = first_tokens
+                    .checked_add(steps.checked_mul(step)?)?
+                    .checked_add(partial)?;
+                loop {
+                    let cost = self.scaled_cost(from, from.checked_add(tokens)?, unit, Rounding::Up)?;
+                    if cost <= budget {
+                        break tokens;
+                    }
+                    tokens = tokens.saturating_sub((cost - budget).div_ceil(price).max(1));
+                }
+            }
+            Self::Stepwise { tiers, tier_count } => {
+                let mut position = from;
+                let mut remaining = budget;
+                for (index, tier) in tiers[..*tier_count as usize].iter().enumerate() {
+                    let is_last = index + 1 == *tier_count as usize;
+                    let end = if is_last {
+                        to
+                    } else {
+                        (tier.end as u128).checked_mul(unit)?
+                    };
+                    if position >= end {
+                        continue;
+                    }
+
+                    let price = tier.price as u128;
+                    let tier_cost = price.checked_mul(end - position)?;
+                    if remaining < tier_cost {
+                        position += remaining / price;
+                        break;
+                    }
+                    remaining -= tier_cost;
+                    position = end;
+                }
+                position.checked_sub(from)?
+            }
+        };
+
+        tokens_out.min(token_reserves as u128).try_into().ok()
+    }
+
+    //  fewest tokens to sell back for at least `sol_amount`, with `tokens_sold` already sold
+    pub fn tokens_for_sol_out(&self, tokens_sold: u64, sol_amount: u64, decimals: u8) -> Option<u64> {
+        let refund = |amount: u64| self.cost(tokens_sold - amount, tokens_sold, decimals, Rounding::Down);
+        if refund(tokens_sold)? < sol_amount {
+            return None;
+        }
+
+        let (mut low, mut high) = (0u64, tokens_sold);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if refund(mid)? >= sol_amount {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        Some(low)
+    }
+
+    //  sum of price * base units over [from, to), prices scaled by `PRICE_SCALE`
+    fn scaled_cost(&self, from: u128, to: u128, unit: u128, rounding: Rounding) -> Option<u128> {
+        if to < from {
+            return None;
+        }
+        let amount = to - from;
+
+        match self {
+            Self::ConstantProduct => None,
+            Self::Linear { start_price, slope } => {
+                //  start * amount + slope * (to^2 - from^2) / (2 * unit)
+                let base = (*start_price as u128).checked_mul(amount)?;
+                let area = mul_div(
+                    amount,
+                    from.checked_add(to)?,
+                    unit.checked_mul(2)?,
+                    rounding,
+                )?;
+                base.checked_add(area.checked_mul(*slope as u128)?)
+            }
+            Self::Exponential {
+                start_price,
+                growth_bps,
+                step_size,
+            } => {
+                let step = (*step_size as u128).checked_mul(unit)?;
+                let growth = *growth_bps as u128;
+                let ratio = FIXED_POINT.checked_add(mul_div(
+                    FIXED_POINT,
+                    growth,
+                    BPS_DENOMINATOR,
+                    rounding,
+                )?)?;
+                let (first_step, last_step) = (from / step, to / step);
+
+                let first_price = step_price(*start_price, ratio, first_step, rounding)?;
+                if first_step == last_step {
+                    return first_price.checked_mul(amount);
+                }
+
+                let first_cost =
+                    first_price.checked_mul(step.checked_mul(first_step + 1)? - from)?;
+
+                //  geometric sum over the whole steps in between
+                let (low, high) = match rounding {
+                    Rounding::Up => (Rounding::Down, Rounding::Up),
+                    Rounding::Down => (Rounding::Up, Rounding::Down),
+                };
+                let low_power = pow_fixed(ratio, first_step + 1, low)?;
+                let high_power = pow_fixed(ratio, last_step, high)?;
+                let price_sum = mul_div(
+                    *start_price as u128,
+                    high_power.saturating_sub(low_power),
+                    FIXED_POINT,
+                    rounding,
+                )?;
+                let middle_cost =
+                    mul_div(price_sum, BPS_DENOMINATOR, growth, rounding)?.checked_mul(step)?;
+
+                let last_price = mul_div(*start_price as u128, high_power, FIXED_POINT, rounding)?;
+                let last_cost = last_price.checked_mul(to - last_step * step)?;
+
+                first_cost.checked_add(middle_cost)?.checked_add(last_cost)
+            }
+            Self::Stepwise { tiers, tier_count } => {
+                let mut position = from;
+                let mut total = 0u128;
+                for (index, tier) in tiers[..*tier_count as usize].iter().enumerate() {
+                    if position >= to {
+                        break;
+                    }
+                    let is_last = index + 1 == *tier_count as usize;
+                    let end = if is_last {
+                        to
+                    } else {
+                        (tier.end as u128).checked_mul(unit)?.min(to)
+                    };
+                    if position >= end {
+                        continue;
+                    }
+
+                    total = total.checked_add((tier.price as u128).checked_mul(end - position)?)?;
+                    position = end;
+                }
+                Some(total)
+            }
+        }
+    }
+}
+
+fn step_price(start_price: u64, ratio: u128, step: u128, rounding: Rounding) -> Option<u128> {
+    mul_div(
+        start_price as u128,
+        pow_fixed(ratio, step, rounding)?,
+        FIXED_POINT,
+        rounding,
+    )
+}
+
+//  base^exponent in `FIXED_POINT`, every intermediate product rounded the same way
+fn pow_fixed(base: u128, mut exponent: u128, rounding: Rounding) -> Option<u128> {
+    let mut result = FIXED_POINT;
+    let mut base = base;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_div(result, base, FIXED_POINT, rounding)?;
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = mul_div(base, base, FIXED_POINT, rounding)?;
+        }
+    }
+    Some(result)
+}
+
+pub fn mul_div(a: u128, b: u128, denominator: u128, rounding: Rounding) -> Option<u128> {
+    let product = a.checked_mul(b)?;
+    match rounding {
+        Rounding::Down => product.checked_div(denominator),
+        Rounding::Up => product
+            .checked_add(denominator.checked_sub(1)?)?
+            .checked_div(denominator),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DECIMALS: u8 = 6;
+    const RESERVES: u64 = 793_100_000_000_000;
+
+    fn curve_kinds() -> [CurveKind; 3] {
+        let mut tiers = [PriceTier::default(); MAX_PRICE_TIERS];
+        tiers[0] = PriceTier {
+            end: 100_000_000,
+            price: 10_000_000_000,
+        };
+        tiers[1] = PriceTier {
+            end: 400_000_000,
+            price: 30_000_000_000,
+        };
+        tiers[2] = PriceTier {
+            end: 793_100_000,
+            price: 60_000_000_000,
+        };
+
+        [
+            CurveKind::Linear {
+                start_price: 14_000_000_000,
+                slope: 100,
+            },
+            CurveKind::Exponential {
+                start_price: 14_000_000_000,
+                growth_bps: 100,
+                step_size: 10_000_000,
+            },
+            CurveKind::Stepwise {
+                tiers,
+                tier_count: 3,
+            },
+        ]
+    }
+
+    //  deterministic xorshift so the property tests need no extra dependency
+    fn samples(seed: u64, modulo: u64) -> impl Iterator<Item = u64> {
+        let mut state = seed;
+        std::iter::from_fn(move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            Some(state % modulo + 1)
+        })
+        .take(100)
+    }
+
+    #[test]
+    fn tokens_for_sol_never_costs_more_than_the_sol_offered() {
+        for kind in curve_kinds() {
+            for (sold, sol_amount) in samples(3, RESERVES).zip(samples(5, 20_000_000_000)) {
+                let tokens_out = kind
+                    .tokens_for_sol(sold, RESERVES - sold, sol_amount, DECIMALS)
+                    .unwrap();
+                let cost = kind
+                    .cost(sold, sold + tokens_out, DECIMALS, Rounding::Up)
+                    .unwrap();
+                assert!(cost <= sol_amount, "{kind:?}: {cost} > {sol_amount}");
+
+                //  and leaves at most a couple of lamports unspent below the next token
+                if tokens_out < RESERVES - sold {
+                    let next = kind
+                        .cost(sold, sold + tokens_out + 2, DECIMALS, Rounding::Up)
+                        .unwrap();
+                    assert!(
+                        next + 2 > sol_amount,
+                        "{kind:?}: {tokens_out} for {sol_amount}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn selling_back_never_returns_more_than_buying_cost() {
+        for kind in curve_kinds() {
+            for (sold, amount) in samples(11, RESERVES / 2).zip(samples(13, RESERVES / 2)) {
+                let bought = kind
+                    .cost(sold, sold + amount, DECIMALS, Rounding::Up)
+                    .unwrap();
+                let sold_back = kind
+                    .cost(sold, sold + amount, DECIMALS, Rounding::Down)
+                    .unwrap();
+                assert!(sold_back <= bought, "{kind:?}");
+                assert!(bought - sold_back <= 2, "{kind:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn cost_is_additive_across_splits() {
+        for kind in curve_kinds() {
+            let total = kind.cost(0, RESERVES, DECIMALS, Rounding::Down).unwrap();
+            let mut position = 0;
+            let mut split_up = 0;
+            let mut split_down = 0;
+            for amount in samples(17, RESERVES / 20) {
+                let next = (position + amount).min(RESERVES);
+                split_up += kind.cost(position, next, DECIMALS, Rounding::Up).unwrap();
+                split_down += kind.cost(position, next, DECIMALS, Rounding::Down).unwrap();
+                position = next;
+            }
+            let rest_up = kind
+                .cost(position, RESERVES, DECIMALS, Rounding::Up)
+                .unwrap();
+            let rest_down = kind
+                .cost(position, RESERVES, DECIMALS, Rounding::Down)
+                .unwrap();
+
+            //  splitting a buy never makes the curve cheaper
+            assert!(split_up + rest_up >= total, "{kind:?}");
+            assert!(split_down + rest_down <= total + 1, "{kind:?}");
+        }
+    }
+
+    #[test]
+    fn prices_follow_the_configured_shape() {
+        let [linear, exponential, stepwise] = curve_kinds();
+        let whole = 10u64.pow(DECIMALS as u32);
+
+        //  one whole token at the start and after 500M tokens sold
+        assert_eq!(linear.cost(0, whole, DECIMALS, Rounding::Down), Some(14));
+        assert_eq!(
+            linear.cost(
+                500_000_000 * whole,
+                500_000_001 * whole,
+                DECIMALS,
+                Rounding::Down
+            ),
+            Some(64)
+        );
+
+        //  1% more every 10M tokens
+        let step = 10_000_000 * whole;
+        let first = exponential.cost(0, step, DECIMALS, Rounding::Down).unwrap();
+        let second = exponential
+            .cost(step, 2 * step, DECIMALS, Rounding::Down)
+            .unwrap();
+        assert_eq!(first, 140_000_000);
+        assert_eq!(second, 141_400_000);
+
+        assert_eq!(stepwise.cost(0, whole, DECIMALS, Rounding::Down), Some(10));
+        assert_eq!(
+            stepwise.cost(
+                99_999_999 * whole,
+                100_000_001 * whole,
+                DECIMALS,
+                Rounding::Down
+            ),
+            Some(40)
+        );
+        assert_eq!(
+            stepwise.cost(RESERVES, RESERVES + whole, DECIMALS, Rounding::Down),
+            Some(60)
+        );
+    }
+
+    #[test]
+    fn tokens_for_sol_out_is_the_fewest_tokens_covering_the_sol() {
+        for kind in curve_kinds() {
+            for (sold, sol_amount) in samples(19, RESERVES).zip(samples(23, 5_000_000_000)) {
+                let refund = |amount| kind.cost(sold - amount, sold, DECIMALS, Rounding::Down).unwrap();
+                let Some(tokens_in) = kind.tokens_for_sol_out(sold, sol_amount, DECIMALS) else {
+                    assert!(refund(sold) < sol_amount);
+                    continue;
+                };
+                assert!(refund(tokens_in) >= sol_amount, "{kind:?}");
+                assert!(tokens_in == 0 || refund(tokens_in - 1) < sol_amount, "{kind:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_params_are_rejected() {
+        for kind in curve_kinds() {
+            assert!(kind.validate().is_ok());
+        }
+        assert!(CurveKind::ConstantProduct.validate().is_ok());
+
+        let invalid = [
+            CurveKind::Linear {
+                start_price: 0,
+                slope: 1,
+            },
+            CurveKind::Exponential {
+                start_price: 1,
+                growth_bps: 0,
+                step_size: 1,
+            },
+            CurveKind::Exponential {
+                start_price: 1,
+                growth_bps: 1,
+                step_size: 0,
+            },
+            CurveKind::Stepwise {
+                tiers: [PriceTier::default(); MAX_PRICE_TIERS],
+                tier_count: 0,
+            },
+            CurveKind::Stepwise {
+                tiers: [PriceTier { end: 1, price: 1 }; MAX_PRICE_TIERS],
+                tier_count: 2,
+            },
+            CurveKind::Stepwise {
+                tiers: [PriceTier::default(); MAX_PRICE_TIERS],
+                tier_count: 9,
+            },
+        ];
+        for kind in invalid {
+            assert!(kind.validate().is_err(), "{kind:?}");
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use crate::errors::*;
+use anchor_lang::prelude::*;
+
+//  running totals of the trades a referrer brought in, created by the referrer with
+//  `register_referrer` before it can be passed to buys and sells
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct ReferrerStats {
+    pub referrer: Pubkey,
+    pub trade_count: u64,
+    //  SOL entering or leaving curves in referred trades, fees excluded
+    pub volume: u64,
+    pub fees_earned: u64,
+    pub bump: u8,
+}
+
+impl ReferrerStats {
+    pub fn record_trade(&mut self, volume: u64, fee: u64) -> Result<()> {
+        self.trade_count = self
+            .trade_count
+            .checked_add(1)
+            .ok_or(ContractError::ArithmeticError)?;
+        self.volume = self
+            .volume
+            .checked_add(volume)
+            .ok_or(ContractError::ArithmeticError)?;
+        self.fees_earned = self
+            .fees_earned
+            .checked_add(fee)
+            .ok_or(ContractError::ArithmeticError)?;
+
+        Ok(())
+    }
+}
+
+//  the referrer of a trade with its stats, paid its slice of the platform fee by `swap`
+pub struct Referral<'a, 'info> {
+    pub referrer: &'a AccountInfo<'info>,
+    pub stats: &'a mut Account<'info, ReferrerStats>,
+}
+
+impl<'a, 'info> Referral<'a, 'info> {
+    //  both accounts or neither, traders can't refer themselves
+    pub fn new(
+        referrer: Option<&'a AccountInfo<'info>>,
+        stats: Option<&'a mut Account<'info, ReferrerStats>>,
+        user: &Pubkey,
+    ) -> Result<Option<Self>> {
+        match (referrer, stats) {
+            (None, None) => Ok(None),
+            (Some(referrer), Some(stats)) => {
+                require!(
+                    stats.referrer == referrer.key(),
+                    ContractError::IncorrectReferrer
+                );
+                require!(referrer.key() != *user, ContractError::SelfReferral);
+                Ok(Some(Self { referrer, stats }))
+            }
+            _ => err!(ContractError::IncorrectReferrer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trades_add_up() {
+        let mut stats = ReferrerStats {
+            referrer: Pubkey::new_unique(),
+            trade_count: 0,
+            volume: 0,
+            fees_earned: 0,
+            bump: 255,
+        };
+
+        stats.record_trade(1_000_000_000, 2_000_000).unwrap();
+        stats.record_trade(500_000_000, 0).unwrap();
+        assert_eq!(stats.trade_count, 2);
+        assert_eq!(stats.volume, 1_500_000_000);
+        assert_eq!(stats.fees_earned, 2_000_000);
+
+        assert!(stats.record_trade(u64::MAX, 0).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+
+//  escrow of the team share of a launch, held in the ata of this pda and claimed
+//  by the config's team wallet as it unlocks
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct TeamVesting {
+    pub token_mint: Pubkey,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+
+    //  copied from `Config` at launch, so later config changes don't move running schedules
+    pub start_time: i64,
+    pub cliff: i64,
+    pub duration: i64,
+
+    pub bump: u8,
+}
+
+impl TeamVesting {
+    //  nothing before the cliff, then linear from `start_time` until `duration` has passed
+    pub fn vested_amount(&self, now: i64) -> u64 {
+        let elapsed = now.saturating_sub(self.start_time);
+        if elapsed < self.cliff {
+            return 0;
+        }
+        if elapsed >= self.duration {
+            return self.total_amount;
+        }
+
+        (self.total_amount as u128 * elapsed as u128 / self.duration as u128) as u64
+    }
+
+    pub fn claimable_amount(&self, now: i64) -> u64 {
+        self.vested_amount(now).saturating_sub(self.claimed_amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vesting(cliff: i64, duration: i64) -> TeamVesting {
+        TeamVesting {
+            token_mint: Pubkey::new_unique(),
+            total_amount: 1_000_000,
+            claimed_amount: 0,
+            start_time: 1_000,
+            cliff,
+            duration,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn unlocks_linearly_after_the_cliff() {
+        let mut vesting = vesting(100, 1_000);
+
+        assert_eq!(vesting.vested_amount(0), 0);
+        assert_eq!(vesting.vested_amount(1_099), 0);
+        assert_eq!(vesting.vested_amount(1_100), 100_000);
+        assert_eq!(vesting.vested_amount(1_500), 500_000);
+        assert_eq!(vesting.vested_amount(2_000), 1_000_000);
+        assert_eq!(vesting.vested_amount(i64::MAX), 1_000_000);
+
+        vesting.claimed_amount = 500_000;
+        assert_eq!(vesting.claimable_amount(1_400), 0);
+        assert_eq!(vesting.claimable_amount(1_750), 250_000);
+    }
+
+    #[test]
+    fn zero_duration_unlocks_at_launch() {
+        let vesting = vesting(0, 0);
+
+        assert_eq!(vesting.vested_amount(999), 0);
+        assert_eq!(vesting.vested_amount(1_000), 1_000_000);
+    }
+}
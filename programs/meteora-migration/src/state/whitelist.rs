@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+
+//  marks `creator` as allowed to launch while `Config.whitelist_enabled` is set.
+//  the account existing is the whole check, it is closed to remove the creator again.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct WhitelistEntry {
+    pub creator: Pubkey,
+    pub added_at: i64,
+}
@@ -1,14 +1,14 @@
 use crate::*;
-use anchor_spl::token::{self, Token};
+use anchor_spl::token_interface::{self, TokenInterface};
 use solana_program::program::{invoke, invoke_signed};
-use std::ops::{Div, Mul};
 
-pub fn convert_to_float(value: u64, decimals: u8) -> f64 {
-    (value as f64).div(f64::powf(10.0, decimals as f64))
-}
-
-pub fn convert_from_float(value: f64, decimals: u8) -> u64 {
-    value.mul(f64::powf(10.0, decimals as f64)) as u64
+//  rescales a token amount between decimal precisions, truncating when scaling down
+pub fn scale_token_amount(amount: u64, from_decimals: u8, to_decimals: u8) -> Option<u64> {
+    if to_decimals >= from_decimals {
+        amount.checked_mul(10u64.checked_pow((to_decimals - from_decimals) as u32)?)
+    } else {
+        amount.checked_div(10u64.checked_pow((from_decimals - to_decimals) as u32)?)
+    }
 }
 
 pub fn sol_transfer_from_user<'info>(
@@ -29,46 +29,128 @@ pub fn sol_transfer_from_user<'info>(
     Ok(())
 }
 
-//  transfer token from user
+//  grows a program owned account to `space` bytes, zero filled, with `payer` topping up the rent
+pub fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+) -> Result<()> {
+    if account.data_len() >= space {
+        return Ok(());
+    }
+
+    let rent = Rent::get()?.minimum_balance(space);
+    if account.lamports() < rent {
+        sol_transfer_from_user(
+            payer,
+            account.clone(),
+            system_program,
+            rent - account.lamports(),
+        )?;
+    }
+    account.realloc(space, true)?;
+
+    Ok(())
+}
+
+//  creates the pda `account` with `space` bytes owned by `owner`, like anchor's `init` does.
+//  an address that was already sent lamports can't be created and is allocated and assigned instead.
+pub fn create_pda_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+    owner: &Pubkey,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    use anchor_lang::system_program::{
+        allocate, assign, create_account, Allocate, Assign, CreateAccount,
+    };
+
+    let rent = Rent::get()?.minimum_balance(space);
+
+    if account.lamports() == 0 {
+        let accounts = CreateAccount {
+            from: payer.to_account_info(),
+            to: account.clone(),
+        };
+        let cpi_ctx =
+            CpiContext::new_with_signer(system_program.to_account_info(), accounts, signer_seeds);
+        return create_account(cpi_ctx, rent, space as u64, owner);
+    }
+
+    if account.lamports() < rent {
+        sol_transfer_from_user(
+            payer,
+            account.clone(),
+            system_program,
+            rent - account.lamports(),
+        )?;
+    }
+
+    let accounts = Allocate {
+        account_to_allocate: account.clone(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(system_program.to_account_info(), accounts, signer_seeds);
+    allocate(cpi_ctx, space as u64)?;
+
+    let accounts = Assign {
+        account_to_assign: account.clone(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(system_program.to_account_info(), accounts, signer_seeds);
+    assign(cpi_ctx, owner)
+}
+
+//  transfer token from user. checked transfers work for both spl token and token-2022 mints
 pub fn token_transfer_user<'info>(
     from: AccountInfo<'info>,
     authority: &Signer<'info>,
     to: AccountInfo<'info>,
-    token_program: &Program<'info, Token>,
+    mint: AccountInfo<'info>,
+    decimals: u8,
+    token_program: &Interface<'info, TokenInterface>,
     amount: u64,
 ) -> Result<()> {
     let cpi_ctx: CpiContext<_> = CpiContext::new(
         token_program.to_account_info(),
-        token::Transfer {
+        token_interface::TransferChecked {
             from,
+            mint,
             authority: authority.to_account_info(),
             to,
         },
     );
-    token::transfer(cpi_ctx, amount)?;
+    token_interface::transfer_checked(cpi_ctx, amount, decimals)?;
 
     Ok(())
 }
 
 //  transfer token from PDA
+#[allow(clippy::too_many_arguments)]
 pub fn token_transfer_with_signer<'info>(
     from: AccountInfo<'info>,
     authority: AccountInfo<'info>,
     to: AccountInfo<'info>,
-    token_program: &Program<'info, Token>,
+    mint: AccountInfo<'info>,
+    decimals: u8,
+    token_program: &Interface<'info, TokenInterface>,
     signer_seeds: &[&[&[u8]]],
     amount: u64,
 ) -> Result<()> {
     let cpi_ctx: CpiContext<_> = CpiContext::new_with_signer(
         token_program.to_account_info(),
-        token::Transfer {
+        token_interface::TransferChecked {
             from,
+            mint,
             to,
             authority,
         },
         signer_seeds,
     );
-    token::transfer(cpi_ctx, amount)?;
+    token_interface::transfer_checked(cpi_ctx, amount, decimals)?;
 
     Ok(())
 }